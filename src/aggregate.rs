@@ -0,0 +1,199 @@
+//! Aggregates over iterators or slices of `FixedPoint` values.
+//!
+//! Each function accepts anything that iterates over `FixedPoint<R>`
+//! or `&FixedPoint<R>` and returns `None` if there are no values.
+//! Sums are accumulated in Wide so that many thousands of values
+//! neither saturate nor lose precision.  Results are rounded to nearest.
+use core::{borrow::Borrow, iter::Sum};
+
+use crate::{div_round, saturate, sqrt_round, FixedPoint, Spec, Wide};
+
+impl<R> Sum<FixedPoint<R>> for FixedPoint<R>
+where
+    R: Spec,
+{
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = FixedPoint<R>>,
+    {
+        let total: Wide = iter.map(|value| value.0.to_fixed() as Wide).sum();
+        Self(R::from_fixed(saturate(total)))
+    }
+}
+
+impl<'a, R> Sum<&'a FixedPoint<R>> for FixedPoint<R>
+where
+    R: Spec,
+{
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = &'a FixedPoint<R>>,
+    {
+        iter.copied().sum()
+    }
+}
+
+/// The mean value.
+pub fn mean<R, I>(values: I) -> Option<FixedPoint<R>>
+where
+    R: Spec,
+    I: IntoIterator,
+    I::Item: Borrow<FixedPoint<R>>,
+{
+    let mut count: Wide = 0;
+    let mut total: Wide = 0;
+    for value in values {
+        count += 1;
+        total += value.borrow().0.to_fixed() as Wide;
+    }
+    if count > 0 {
+        Some(FixedPoint(R::from_fixed(saturate(div_round(total, count)))))
+    } else {
+        None
+    }
+}
+
+/// The least value.
+pub fn min<R, I>(values: I) -> Option<FixedPoint<R>>
+where
+    R: Spec,
+    I: IntoIterator,
+    I::Item: Borrow<FixedPoint<R>>,
+{
+    values
+        .into_iter()
+        .map(|value| *value.borrow())
+        .min_by_key(|value| value.0.to_fixed())
+}
+
+/// The greatest value.
+pub fn max<R, I>(values: I) -> Option<FixedPoint<R>>
+where
+    R: Spec,
+    I: IntoIterator,
+    I::Item: Borrow<FixedPoint<R>>,
+{
+    values
+        .into_iter()
+        .map(|value| *value.borrow())
+        .max_by_key(|value| value.0.to_fixed())
+}
+
+/// The difference between the greatest and least values.
+pub fn range<R, I>(values: I) -> Option<FixedPoint<R>>
+where
+    R: Spec,
+    I: IntoIterator,
+    I::Item: Borrow<FixedPoint<R>>,
+{
+    let mut bounds = None;
+    for value in values {
+        let fixed = value.borrow().0.to_fixed();
+        bounds = match bounds {
+            Some((lo, hi)) => Some((fixed.min(lo), fixed.max(hi))),
+            None => Some((fixed, fixed)),
+        };
+    }
+    bounds.map(|(lo, hi)| FixedPoint(R::from_fixed(saturate(hi as Wide - lo as Wide))))
+}
+
+/// The root mean square value, for example of a block of AC samples.
+/// The sum of squares is accumulated in 128 bits so it cannot overflow
+/// for any practical number of values.
+/// The result is within one least significant digit of the exact value.
+pub fn rms<R, I>(values: I) -> Option<FixedPoint<R>>
+where
+    R: Spec,
+    I: IntoIterator,
+    I::Item: Borrow<FixedPoint<R>>,
+{
+    let mut count: u128 = 0;
+    let mut total: u128 = 0;
+    for value in values {
+        let fixed = value.borrow().0.to_fixed().unsigned_abs() as u128;
+        count += 1;
+        total += fixed * fixed;
+    }
+    if count == 0 {
+        return None;
+    }
+    // the mean square is no greater than the largest square so fits in u64
+    let square = ((total + count / 2) / count) as u64;
+    Some(FixedPoint(R::from_fixed(saturate(
+        sqrt_round(square) as Wide
+    ))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unit;
+
+    type Current = FixedPoint<unit::Amp>;
+
+    fn samples() -> [Current; 4] {
+        [
+            Current::with_fix1(100),
+            Current::with_fix1(-20),
+            Current::with_fix1(45),
+            Current::with_fix1(60),
+        ]
+    }
+
+    #[test]
+    fn summation() {
+        let samples = samples();
+        assert_eq!(samples.iter().sum::<Current>(), Current::with_fix1(185));
+        assert_eq!(
+            samples.into_iter().sum::<Current>(),
+            Current::with_fix1(185)
+        );
+        let saturated: Current = [Current::with_fix1(i32::MAX); 3].iter().sum();
+        assert_eq!(saturated, Current::with_fix1(i32::MAX));
+    }
+
+    #[test]
+    fn aggregates() {
+        let samples = samples();
+        assert_eq!(mean(samples.as_slice()), Some(Current::with_fix1(46)));
+        assert_eq!(min(samples.as_slice()), Some(Current::with_fix1(-20)));
+        assert_eq!(max(samples), Some(Current::with_fix1(100)));
+        assert_eq!(range(samples.iter()), Some(Current::with_fix1(120)));
+        assert_eq!(rms(samples.as_slice()), Some(Current::with_fix1(63)));
+        assert_eq!(mean::<unit::Amp, _>(&[] as &[Current]), None);
+    }
+
+    #[test]
+    fn rms_reference() {
+        let mut samples = [Current::ZERO; 200];
+        for (i, sample) in samples.iter_mut().enumerate() {
+            let angle = i as f64 * core::f64::consts::TAU / 200.0;
+            *sample = Current::with_fix1((3250.0 * angle.sin() + 7.0).round() as i32);
+        }
+        let reference = (samples
+            .iter()
+            .map(|s| (s.fix1() as f64).powi(2))
+            .sum::<f64>()
+            / 200.0)
+            .sqrt();
+        let fixed = rms(samples.as_slice()).unwrap().fix1();
+        assert!((fixed as f64 - reference).abs() <= 1.0);
+    }
+
+    #[test]
+    fn large_rms() {
+        let samples = [Current::with_fix1(i32::MIN); 4];
+        assert_eq!(rms(samples), Some(Current::with_fix1(i32::MAX)));
+        let samples = [Current::with_fix1(-i32::MAX); 5000];
+        assert_eq!(rms(samples.as_slice()), Some(Current::with_fix1(i32::MAX)));
+    }
+
+    #[test]
+    fn large_mean() {
+        let samples = [Current::with_fix1(i32::MAX - 1); 5000];
+        assert_eq!(
+            mean(samples.as_slice()),
+            Some(Current::with_fix1(i32::MAX - 1))
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{div_round, saturate, shift_round, FixedPoint, Spec, Wide};
+
+/// A linear calibration from raw ADC counts to a `FixedPoint` value,
+/// `value = raw * gain + offset`.
+///
+/// The gain is held in fixed point units of `R` per count with
+/// `GAIN_BITS` fraction bits and the offset is a `FixedPoint<R>`.
+/// Conversion uses integer arithmetic only, rounds to nearest and saturates.
+/// A calibration serializes as its gain and offset so that per-unit
+/// calibration data can be stored.
+///
+/// The gain is serialized as the raw integer, which is the gain in
+/// units of `R` per count multiplied by `2^GAIN_BITS`.  For example,
+/// a gain of 1.5 V per count in `unit::Volt` (0.1 V units) is
+/// `15 * 2^32 = 64424509440`:
+///
+/// ```
+/// use fixed_point::{calibration::Calibration, unit, FixedPoint};
+///
+/// let cal: Calibration<unit::Volt> =
+///     serde_json::from_str(r#"{"gain":64424509440,"offset":-5}"#).unwrap();
+/// assert_eq!(cal.apply(2), FixedPoint::<unit::Volt>::with_fix1(25));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[serde(bound = "")]
+pub struct Calibration<R>
+where
+    R: Spec,
+{
+    /// Units of `R` per count times `2^GAIN_BITS`, so 4294967296 is
+    /// one unit of `R` per count.
+    gain: Wide,
+    offset: FixedPoint<R>,
+}
+
+/// The number of fraction bits in a calibration gain.
+pub const GAIN_BITS: u32 = 32;
+
+impl<R> Calibration<R>
+where
+    R: Spec,
+{
+    /// Construct from a raw gain with GAIN_BITS fraction bits and an offset.
+    pub const fn new(gain: Wide, offset: FixedPoint<R>) -> Self {
+        Self { gain, offset }
+    }
+
+    /// A calibration where `counts` corresponds to `value` and zero counts to `offset`.
+    /// Gives None if counts is zero.
+    pub fn full_scale(counts: u16, value: FixedPoint<R>, offset: FixedPoint<R>) -> Option<Self> {
+        let span = value.0.to_fixed() as i128 - offset.0.to_fixed() as i128;
+        let gain = gain(span, counts as i128)?;
+        Some(Self { gain, offset })
+    }
+
+    /// A calibration through two reference measurements, each a raw reading
+    /// and the corresponding value.  Gives None if the raw readings are equal.
+    pub fn two_point(low: (u16, FixedPoint<R>), high: (u16, FixedPoint<R>)) -> Option<Self> {
+        let (raw0, value0) = (low.0 as i128, low.1 .0.to_fixed() as i128);
+        let (raw1, value1) = (high.0 as i128, high.1 .0.to_fixed() as i128);
+        let gain = gain(value1 - value0, raw1 - raw0)?;
+        let offset = value0 - shift_round(raw0 * gain as i128, GAIN_BITS);
+        let offset = FixedPoint(R::from_fixed(saturate(offset as Wide)));
+        Some(Self { gain, offset })
+    }
+
+    /// The raw gain with GAIN_BITS fraction bits.
+    pub fn gain(&self) -> Wide {
+        self.gain
+    }
+
+    pub fn offset(&self) -> FixedPoint<R> {
+        self.offset
+    }
+
+    /// Convert a raw reading.
+    pub fn apply(&self, raw: u16) -> FixedPoint<R> {
+        let value = shift_round(raw as i128 * self.gain as i128, GAIN_BITS)
+            + self.offset.0.to_fixed() as i128;
+        FixedPoint(R::from_fixed(saturate(
+            value.clamp(Wide::MIN as i128, Wide::MAX as i128) as Wide,
+        )))
+    }
+
+    /// Convert a slice of raw readings into a slice of values.
+    /// Conversion stops at the end of the shorter slice.
+    pub fn apply_slice(&self, raw: &[u16], values: &mut [FixedPoint<R>]) {
+        for (value, raw) in values.iter_mut().zip(raw) {
+            *value = self.apply(*raw);
+        }
+    }
+}
+
+/// The gain for a span of fixed values over a span of counts.
+fn gain(values: i128, counts: i128) -> Option<Wide> {
+    if counts == 0 {
+        return None;
+    }
+    let gain = div_round(values << GAIN_BITS, counts);
+    Some(gain.clamp(Wide::MIN as i128, Wide::MAX as i128) as Wide)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unit;
+
+    type Voltage = FixedPoint<unit::Volt>;
+    type Current = FixedPoint<unit::Amp>;
+
+    #[test]
+    fn full_scale() {
+        let cal = Calibration::full_scale(4095, Voltage::with_fix1(4000), Voltage::ZERO).unwrap();
+        assert_eq!(cal.apply(0), Voltage::ZERO);
+        assert_eq!(cal.apply(4095), Voltage::with_fix1(4000));
+        assert_eq!(cal.apply(2048), Voltage::with_fix1(2000));
+        assert_eq!(cal.apply(1), Voltage::with_fix1(1));
+        assert!(Calibration::full_scale(0, Voltage::with_fix1(4000), Voltage::ZERO).is_none());
+    }
+
+    #[test]
+    fn two_point() {
+        // a bidirectional current sensor centred at mid scale
+        let cal = Calibration::two_point(
+            (410, Current::with_fix1(-500)),
+            (3686, Current::with_fix1(500)),
+        )
+        .unwrap();
+        assert_eq!(cal.apply(2048), Current::with_fix1(0));
+        assert_eq!(cal.apply(410), Current::with_fix1(-500));
+        assert_eq!(cal.apply(3686), Current::with_fix1(500));
+        assert_eq!(cal.apply(0), Current::with_fix1(-625));
+        assert!(Calibration::two_point((5, Current::ZERO), (5, Current::with_fix1(1))).is_none());
+    }
+
+    #[test]
+    fn batch() {
+        let cal = Calibration::full_scale(1000, Voltage::with_fix1(100), Voltage::with_fix1(-50))
+            .unwrap();
+        let mut values = [Voltage::ZERO; 3];
+        cal.apply_slice(&[0, 500, 1000], &mut values);
+        assert_eq!(
+            values,
+            [
+                Voltage::with_fix1(-50),
+                Voltage::with_fix1(25),
+                Voltage::with_fix1(100)
+            ]
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let cal = Calibration::new(3 << 31, Voltage::with_fix1(-5));
+        let text = serde_json::to_string(&cal).unwrap();
+        assert_eq!(text, r#"{"gain":6442450944,"offset":-5}"#);
+        assert_eq!(
+            serde_json::from_str::<Calibration<unit::Volt>>(&text).unwrap(),
+            cal
+        );
+        assert_eq!(cal.apply(10), Voltage::with_fix1(10));
+    }
+}
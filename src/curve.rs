@@ -0,0 +1,255 @@
+use core::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{div_round, saturate, FixedPoint, Spec, Wide};
+
+/// A piecewise linear function given by `N` breakpoints `(x, y)`,
+/// such as a derating curve or a sensor calibration table.
+///
+/// The x values must be strictly increasing.  A curve can be
+/// constructed in a const context with `new` and checked with
+/// `validate` or constructed with `try_new`.  Evaluation uses
+/// integer arithmetic and clamps to the first and last y values
+/// outside the range of x.
+///
+/// A curve serializes as a sequence of `[x, y]` pairs and is
+/// validated when deserialized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Curve<X, Y, const N: usize>
+where
+    X: Spec,
+    Y: Spec,
+{
+    points: [(FixedPoint<X>, FixedPoint<Y>); N],
+}
+
+/// The reason a curve is invalid.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CurveError {
+    /// The x values are not strictly increasing at the given index.
+    NotIncreasing(usize),
+}
+
+impl fmt::Display for CurveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CurveError::NotIncreasing(index) => {
+                write!(f, "curve x values are not increasing at point {index}")
+            }
+        }
+    }
+}
+
+impl<X, Y, const N: usize> Curve<X, Y, N>
+where
+    X: Spec,
+    Y: Spec,
+{
+    /// Construct without validation.
+    /// A curve must have at least one point, which is checked at compile time.
+    pub const fn new(points: [(FixedPoint<X>, FixedPoint<Y>); N]) -> Self {
+        const { assert!(N > 0, "a curve needs at least one point") };
+        Self { points }
+    }
+
+    pub fn points(&self) -> &[(FixedPoint<X>, FixedPoint<Y>); N] {
+        &self.points
+    }
+
+    /// Construct with validation.
+    pub fn try_new(points: [(FixedPoint<X>, FixedPoint<Y>); N]) -> Result<Self, CurveError> {
+        let curve = Self::new(points);
+        curve.validate()?;
+        Ok(curve)
+    }
+
+    /// Check the curve has strictly increasing x values.
+    pub fn validate(&self) -> Result<(), CurveError> {
+        for (index, pair) in self.points.windows(2).enumerate() {
+            if pair[0].0 .0.to_fixed() >= pair[1].0 .0.to_fixed() {
+                return Err(CurveError::NotIncreasing(index + 1));
+            }
+        }
+        Ok(())
+    }
+
+    /// True if y never decreases or never increases with x,
+    /// in which case the curve has an inverse over its range.
+    pub fn is_monotonic(&self) -> bool {
+        let steps = || {
+            self.points
+                .windows(2)
+                .map(|pair| pair[1].1 .0.to_fixed().cmp(&pair[0].1 .0.to_fixed()))
+        };
+        steps().all(|step| step.is_ge()) || steps().all(|step| step.is_le())
+    }
+
+    /// Interpolate the y value for x, rounded to nearest.
+    /// The curve must be valid.
+    pub fn eval(&self, x: FixedPoint<X>) -> FixedPoint<Y> {
+        let x = x.0.to_fixed();
+        let index = self
+            .points
+            .partition_point(|point| point.0 .0.to_fixed() <= x);
+        if index == 0 {
+            return self.points[0].1;
+        }
+        if index == N {
+            return self.points[N - 1].1;
+        }
+        let (x0, y0) = self.points[index - 1];
+        let (x1, y1) = self.points[index];
+        let (x0, y0) = (x0.0.to_fixed() as i128, y0.0.to_fixed() as i128);
+        let (x1, y1) = (x1.0.to_fixed() as i128, y1.0.to_fixed() as i128);
+
+        let y = y0 + div_round((y1 - y0) * (x as i128 - x0), x1 - x0);
+        FixedPoint(Y::from_fixed(saturate(y as Wide)))
+    }
+}
+
+impl<X, Y, const N: usize> Serialize for Curve<X, Y, N>
+where
+    X: Spec,
+    Y: Spec,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.points.iter())
+    }
+}
+
+impl<'de, X, Y, const N: usize> Deserialize<'de> for Curve<X, Y, N>
+where
+    X: Spec,
+    Y: Spec,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(CurveVisitor(PhantomData))
+    }
+}
+
+struct CurveVisitor<X, Y, const N: usize>(PhantomData<(X, Y)>);
+
+impl<'de, X, Y, const N: usize> Visitor<'de> for CurveVisitor<X, Y, N>
+where
+    X: Spec,
+    Y: Spec,
+{
+    type Value = Curve<X, Y, N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a sequence of {N} points")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let zero = (FixedPoint(X::from_fixed(0)), FixedPoint(Y::from_fixed(0)));
+        let mut points = [zero; N];
+        for (index, point) in points.iter_mut().enumerate() {
+            *point = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(index, &self))?;
+        }
+        if seq
+            .next_element::<(FixedPoint<X>, FixedPoint<Y>)>()?
+            .is_some()
+        {
+            return Err(de::Error::invalid_length(N + 1, &self));
+        }
+        Curve::try_new(points).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unit;
+
+    type Current = FixedPoint<unit::Amp>;
+    type Temperature = FixedPoint<unit::Celsius>;
+
+    const DERATING: Curve<unit::Celsius, unit::Amp, 3> = Curve::new([
+        (Temperature::with_fix2(4000), Current::with_fix1(320)),
+        (Temperature::with_fix2(6000), Current::with_fix1(160)),
+        (Temperature::with_fix2(7000), Current::with_fix1(0)),
+    ]);
+
+    #[test]
+    fn interpolation() {
+        assert_eq!(
+            DERATING.eval(Temperature::with_fix2(-1000)),
+            Current::with_fix1(320)
+        );
+        assert_eq!(
+            DERATING.eval(Temperature::with_fix2(4000)),
+            Current::with_fix1(320)
+        );
+        assert_eq!(
+            DERATING.eval(Temperature::with_fix2(5000)),
+            Current::with_fix1(240)
+        );
+        assert_eq!(
+            DERATING.eval(Temperature::with_fix2(5001)),
+            Current::with_fix1(240)
+        );
+        assert_eq!(
+            DERATING.eval(Temperature::with_fix2(5007)),
+            Current::with_fix1(239)
+        );
+        assert_eq!(
+            DERATING.eval(Temperature::with_fix2(6000)),
+            Current::with_fix1(160)
+        );
+        assert_eq!(
+            DERATING.eval(Temperature::with_fix2(6550)),
+            Current::with_fix1(72)
+        );
+        assert_eq!(
+            DERATING.eval(Temperature::with_fix2(9000)),
+            Current::with_fix1(0)
+        );
+    }
+
+    #[test]
+    fn validation() {
+        assert_eq!(DERATING.validate(), Ok(()));
+        assert!(DERATING.is_monotonic());
+        let curve = Curve::try_new([
+            (Temperature::with_fix2(0), Current::with_fix1(0)),
+            (Temperature::with_fix2(0), Current::with_fix1(10)),
+        ]);
+        assert_eq!(curve.err(), Some(CurveError::NotIncreasing(1)));
+        let curve = Curve::new([
+            (Temperature::with_fix2(0), Current::with_fix1(0)),
+            (Temperature::with_fix2(1), Current::with_fix1(10)),
+            (Temperature::with_fix2(2), Current::with_fix1(5)),
+        ]);
+        assert!(!curve.is_monotonic());
+    }
+
+    #[test]
+    fn serialization() {
+        let text = serde_json::to_string(&DERATING).unwrap();
+        assert_eq!(text, "[[4000,320],[6000,160],[7000,0]]");
+        let curve: Curve<unit::Celsius, unit::Amp, 3> = serde_json::from_str(&text).unwrap();
+        assert_eq!(curve, DERATING);
+        assert!(serde_json::from_str::<Curve<unit::Celsius, unit::Amp, 2>>(&text).is_err());
+        assert!(serde_json::from_str::<Curve<unit::Celsius, unit::Amp, 4>>(&text).is_err());
+        assert!(
+            serde_json::from_str::<Curve<unit::Celsius, unit::Amp, 2>>("[[1,0],[0,1]]").is_err()
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use core::marker::PhantomData;
+
+use crate::{
+    div_round, phases::PhasesOpt, ratio::Ratio, saturate, unit, Fixed, FixedPoint, Spec, Wide,
+};
+
+/// A filter over a stream of values.
+///
+/// The filters here operate on `FixedPoint` values with integer arithmetic
+/// and fixed capacity.  Any filter can be applied to each phase of a
+/// `PhasesOpt` stream with `PhasesFilter`.
+pub trait Filter<T> {
+    /// Accept the next input value and give the filtered output.
+    fn update(&mut self, input: T) -> T;
+
+    /// Discard all history.
+    fn reset(&mut self);
+}
+
+/// An exponential moving average, `y += alpha * (x - y)`.
+///
+/// The state is held with the precision of alpha so that small
+/// steps are not lost to rounding.  The first input initialises the state.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ema<R> {
+    alpha: Wide,
+    state: Option<Wide>,
+    marker: PhantomData<R>,
+}
+
+impl<R> Ema<R> {
+    /// Construct with a smoothing factor between 0 and 1.
+    /// A factor outside that range is clamped to it.
+    pub const fn new(alpha: Ratio) -> Self {
+        let alpha = alpha.fix4() as Wide;
+        Self {
+            alpha: if alpha < 0 {
+                0
+            } else if alpha > unit::Ratio::DENOM {
+                unit::Ratio::DENOM
+            } else {
+                alpha
+            },
+            state: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<R> Filter<FixedPoint<R>> for Ema<R>
+where
+    R: Spec,
+{
+    fn update(&mut self, input: FixedPoint<R>) -> FixedPoint<R> {
+        let input = input.0.to_fixed() as Wide * unit::Ratio::DENOM;
+        let state = match self.state {
+            Some(state) => state + div_round(self.alpha * (input - state), unit::Ratio::DENOM),
+            None => input,
+        };
+        self.state = Some(state);
+        FixedPoint(R::from_fixed(saturate(div_round(
+            state,
+            unit::Ratio::DENOM,
+        ))))
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+/// A boxcar moving average of the last `N` values.
+/// Until `N` values are received the average is of those received.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MovingAverage<R, const N: usize> {
+    window: Window<N>,
+    sum: Wide,
+    marker: PhantomData<R>,
+}
+
+impl<R, const N: usize> MovingAverage<R, N> {
+    pub const fn new() -> Self {
+        Self {
+            window: Window::new(),
+            sum: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<R, const N: usize> Default for MovingAverage<R, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R, const N: usize> Filter<FixedPoint<R>> for MovingAverage<R, N>
+where
+    R: Spec,
+{
+    fn update(&mut self, input: FixedPoint<R>) -> FixedPoint<R> {
+        let input = input.0.to_fixed();
+        if let Some(expired) = self.window.push(input) {
+            self.sum -= expired as Wide;
+        }
+        self.sum += input as Wide;
+        let mean = div_round(self.sum, self.window.len as Wide);
+        FixedPoint(R::from_fixed(saturate(mean)))
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+/// A median of the last `N` values, which rejects isolated spikes.
+/// For an even number of values the two middle values are averaged.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Median<R, const N: usize> {
+    window: Window<N>,
+    marker: PhantomData<R>,
+}
+
+impl<R, const N: usize> Median<R, N> {
+    pub const fn new() -> Self {
+        Self {
+            window: Window::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<R, const N: usize> Default for Median<R, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R, const N: usize> Filter<FixedPoint<R>> for Median<R, N>
+where
+    R: Spec,
+{
+    fn update(&mut self, input: FixedPoint<R>) -> FixedPoint<R> {
+        self.window.push(input.0.to_fixed());
+        let mut sorted = self.window.values;
+        let sorted = &mut sorted[..self.window.len];
+        sorted.sort_unstable();
+        let mid = sorted.len() / 2;
+        let median = if sorted.len() % 2 == 1 {
+            sorted[mid] as Wide
+        } else {
+            div_round(sorted[mid - 1] as Wide + sorted[mid] as Wide, 2)
+        };
+        FixedPoint(R::from_fixed(saturate(median)))
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+/// A filter for each phase of a `PhasesOpt` stream.
+/// When a phase is out of service its output is None
+/// and its filter is reset.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PhasesFilter<F>(pub F, pub F, pub F);
+
+impl<F> PhasesFilter<F>
+where
+    F: Clone,
+{
+    /// Use copies of the given filter for each phase.
+    pub fn new(filter: F) -> Self {
+        Self(filter.clone(), filter.clone(), filter)
+    }
+}
+
+impl<T, F> Filter<PhasesOpt<T>> for PhasesFilter<F>
+where
+    F: Filter<T>,
+{
+    fn update(&mut self, input: PhasesOpt<T>) -> PhasesOpt<T> {
+        PhasesOpt(
+            update_opt(&mut self.0, input.0),
+            update_opt(&mut self.1, input.1),
+            update_opt(&mut self.2, input.2),
+        )
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+        self.1.reset();
+        self.2.reset();
+    }
+}
+
+fn update_opt<T, F>(filter: &mut F, input: Option<T>) -> Option<T>
+where
+    F: Filter<T>,
+{
+    match input {
+        Some(input) => Some(filter.update(input)),
+        None => {
+            filter.reset();
+            None
+        }
+    }
+}
+
+/// A ring buffer of the last `N` fixed values.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+struct Window<const N: usize> {
+    values: [Fixed; N],
+    len: usize,
+    next: usize,
+}
+
+impl<const N: usize> Window<N> {
+    const fn new() -> Self {
+        const { assert!(N > 0, "a filter window must not be empty") };
+        Self {
+            values: [0; N],
+            len: 0,
+            next: 0,
+        }
+    }
+
+    /// Add a value, giving the value it displaces once the window is full.
+    fn push(&mut self, value: Fixed) -> Option<Fixed> {
+        let expired = if self.len == N {
+            Some(self.values[self.next])
+        } else {
+            self.len += 1;
+            None
+        };
+        self.values[self.next] = value;
+        self.next = (self.next + 1) % N;
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Current = FixedPoint<unit::Amp>;
+    type Temperature = FixedPoint<unit::Celsius>;
+
+    fn run<F, R>(filter: &mut F, inputs: &[Fixed]) -> Vec<Fixed>
+    where
+        F: Filter<FixedPoint<R>>,
+        R: Spec,
+    {
+        inputs
+            .iter()
+            .map(|input| {
+                filter
+                    .update(FixedPoint(R::from_fixed(*input)))
+                    .0
+                    .to_fixed()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ema() {
+        let mut filter = Ema::<unit::Amp>::new(Ratio::percent(50));
+        assert_eq!(
+            run(&mut filter, &[100, 200, 200, 200, 0, 0]),
+            [100, 150, 175, 188, 94, 47]
+        );
+        let mut filter = Ema::<unit::Amp>::new(Ratio::percent(1));
+        let mut output = filter.update(Current::ZERO);
+        for _ in 0..100 {
+            output = filter.update(Current::with_fix1(1));
+        }
+        assert_eq!(output, Current::with_fix1(1));
+        assert_eq!(filter.update(Current::ZERO), Current::with_fix1(1));
+        filter.reset();
+        assert_eq!(filter.update(Current::with_fix1(7)), Current::with_fix1(7));
+    }
+
+    #[test]
+    fn ema_alpha_clamped() {
+        let mut filter = Ema::<unit::Amp>::new(Ratio::with_fix4(Fixed::MAX));
+        assert_eq!(
+            run(&mut filter, &[100, Fixed::MAX, Fixed::MIN]),
+            [100, Fixed::MAX, Fixed::MIN]
+        );
+        let mut filter = Ema::<unit::Amp>::new(Ratio::with_fix4(-1));
+        assert_eq!(run(&mut filter, &[100, 200, 300]), [100, 100, 100]);
+    }
+
+    #[test]
+    fn moving_average() {
+        let mut filter = MovingAverage::<unit::Celsius, 3>::new();
+        assert_eq!(
+            run(&mut filter, &[300, 600, 900, 900, 900, -900]),
+            [300, 450, 600, 800, 900, 300]
+        );
+        filter.reset();
+        assert_eq!(
+            filter.update(Temperature::with_fix2(5)),
+            Temperature::with_fix2(5)
+        );
+    }
+
+    #[test]
+    fn median() {
+        let mut filter = Median::<unit::Amp, 3>::new();
+        assert_eq!(
+            run(&mut filter, &[100, 5000, 110, 105, -3000, 100]),
+            [100, 2550, 110, 110, 105, 100]
+        );
+    }
+
+    #[test]
+    fn phases() {
+        let mut filter = PhasesFilter::new(MovingAverage::<unit::Amp, 2>::new());
+        let a = Current::with_fix1(100);
+        let b = Current::with_fix1(200);
+        filter.update(PhasesOpt(Some(a), Some(a), None));
+        assert_eq!(
+            filter.update(PhasesOpt(Some(b), None, Some(b))),
+            PhasesOpt(Some(Current::with_fix1(150)), None, Some(b))
+        );
+        assert_eq!(
+            filter.update(PhasesOpt(Some(b), Some(b), Some(a))),
+            PhasesOpt(Some(b), Some(b), Some(Current::with_fix1(150)))
+        );
+    }
+}
@@ -1,14 +1,32 @@
 #![cfg_attr(not(test), no_std)]
 
+#[cfg(feature = "float")]
+use core::ops::{DivAssign, MulAssign};
 use core::{
+    cmp::Ordering,
     fmt,
-    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign},
+    ops::{Add, AddAssign, Div, Mul, Neg, Shl, Shr, Sub, SubAssign},
     str::FromStr,
 };
 use serde::{Deserialize, Serialize};
 
+pub mod aggregate;
+pub mod calibration;
+pub mod curve;
+pub mod filter;
+pub mod offset;
 pub mod phases;
+pub mod pid;
+pub mod qformat;
+pub mod quantity;
+pub mod ramp;
+pub mod ratio;
+pub mod stats;
+pub mod temperature;
+pub mod three_phase;
+pub mod threshold;
 pub mod unit;
+pub mod wiring;
 
 /// A generic fixed point numeric type implemented as a tuple-struct that serializes cleanly.
 /// Type parameter `R` is the representation of the number on the wire and in memory.  
@@ -21,18 +39,24 @@ pub mod unit;
 ///
 /// The traits defined on FixedPoint<R> provide all representations with:
 ///
-/// - Conversions to and from Float (f32).
+/// - Conversions to and from Float (f32) with feature `float`.
 /// - Operations add, substract and scaling (ie a linear space).
 /// - Equality and ordering.
 /// - Debug, Display and defmt::Format.
 /// - Parsing from strings.
 /// - Serde.
 ///
-#[derive(Clone, Copy, Default, Deserialize, Eq, PartialEq, Serialize, Ord, PartialOrd)]
+/// Values in different representations of the same quantity,
+/// such as `Volt` and `PreciseVolt`, can be compared exactly.
+/// They are added or subtracted with `add_as` and `sub_as`,
+/// which give the result in an explicitly chosen representation.
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
 pub struct FixedPoint<R>(R);
 
 /// The type of float for scaling and conversion.  
 /// This is f32 for support on microcontrollers.
+/// Without feature `float` there are no float operations.
+#[cfg(feature = "float")]
 type Float = f32;
 
 /// A common integer type for all fixed point representations.
@@ -41,11 +65,168 @@ type Float = f32;
 /// 32 bits in size (signed) or 31 bits (unsigned).
 type Fixed = i32;
 
+/// A wider integer for intermediate results in integer arithmetic.
+/// The product of any two Fixed values fits in Wide.
+type Wide = i64;
+
+/// The integer types of intermediate results, which are Wide
+/// or i128 where a product of Wide values is needed.
+trait Integer
+where
+    Self: Copy
+        + PartialOrd
+        + Add<Output = Self>
+        + Mul<Output = Self>
+        + Div<Output = Self>
+        + Shl<u32, Output = Self>
+        + Shr<u32, Output = Self>,
+{
+    const ZERO: Self;
+    const ONE: Self;
+    fn abs(self) -> Self;
+    fn signum(self) -> Self;
+    fn saturating_add(self, rhs: Self) -> Self;
+    fn saturating_sub(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($int:ty),*) => {
+        $(
+            impl Integer for $int {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                fn abs(self) -> Self {
+                    <$int>::abs(self)
+                }
+                fn signum(self) -> Self {
+                    <$int>::signum(self)
+                }
+                fn saturating_add(self, rhs: Self) -> Self {
+                    <$int>::saturating_add(self, rhs)
+                }
+                fn saturating_sub(self, rhs: Self) -> Self {
+                    <$int>::saturating_sub(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(Wide, i128);
+
+/// Divide, rounding to the nearest integer with ties away from zero.
+/// The divisor must not be zero.
+fn div_round<T>(numer: T, denom: T) -> T
+where
+    T: Integer,
+{
+    let half = denom.abs() >> 1;
+    if (numer < T::ZERO) == (denom < T::ZERO) {
+        numer.saturating_add(half * denom.signum()) / denom
+    } else {
+        numer.saturating_sub(half * denom.signum()) / denom
+    }
+}
+
+/// Shift right, rounding to nearest with ties upward.
+fn shift_round<T>(value: T, bits: u32) -> T
+where
+    T: Integer,
+{
+    if bits == 0 {
+        value
+    } else {
+        (value + (T::ONE << (bits - 1))) >> bits
+    }
+}
+
+/// Compute `numer * scale / denom` rounding as `div_round`
+/// and saturating according to the sign of the numerator on division by zero.
+fn div_saturate(numer: Fixed, denom: Fixed, scale: Wide) -> Wide {
+    let numer = numer as Wide * scale;
+    if denom != 0 {
+        div_round(numer, denom as Wide)
+    } else {
+        numer.signum() * Wide::MAX
+    }
+}
+
+/// The factor that converts fixed values of `R` to fixed values of `S`
+/// as a numerator and a positive denominator.
+fn conversion<R, S>() -> (i128, i128)
+where
+    R: Spec,
+    S: Spec,
+{
+    let power = |exponent: i32| 10i128.pow(exponent.unsigned_abs());
+    let prefix = R::PREFIX - S::PREFIX;
+    if R::DENOM == S::DENOM && prefix == 0 {
+        (1, 1)
+    } else if prefix >= 0 {
+        (S::DENOM as i128 * power(prefix), R::DENOM as i128)
+    } else {
+        (S::DENOM as i128, R::DENOM as i128 * power(prefix))
+    }
+}
+
+/// A fixed value of `R` in the fixed units of `S`, rounded to nearest
+/// and saturating at the limits of Wide.
+fn convert<R, S>(fixed: Fixed) -> Wide
+where
+    R: Spec,
+    S: Spec,
+{
+    match conversion::<R, S>() {
+        (1, 1) => fixed as Wide,
+        (numer, denom) => div_round(fixed as i128 * numer, denom)
+            .clamp(Wide::MIN as i128, Wide::MAX as i128) as Wide,
+    }
+}
+
+/// Integer square root rounded to nearest.
+fn sqrt_round(square: u64) -> u64 {
+    let root = sqrt_floor(square);
+    if square - root * root > root {
+        root + 1
+    } else {
+        root
+    }
+}
+
+/// Integer square root rounded down, by Newton's method
+/// from a first estimate that is not less than the root.
+fn sqrt_floor(square: u64) -> u64 {
+    if square < 2 {
+        return square;
+    }
+    let bits = u64::BITS - square.leading_zeros();
+    let mut root = 1u64 << bits.div_ceil(2);
+    loop {
+        let next = (root + square / root) / 2;
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+/// Narrow a Wide result to Fixed, saturating at the bounds.
+fn saturate(value: Wide) -> Fixed {
+    value.clamp(Fixed::MIN as Wide, Fixed::MAX as Wide) as Fixed
+}
+
 /// The specification of a FixedPoint number.
 ///
-/// The constant Self::SCALE indicates the
+/// The constant Self::DENOM indicates the
 /// size of the fractional part.  A value
-/// is multiplied by SCALE then truncated to Fixed.
+/// is multiplied by DENOM then truncated to Fixed.
+/// Self::SCALE is the same as a Float (with feature `float`).
+///
+/// Self::Quantity is a marker type from module `quantity` and
+/// Self::PREFIX gives the unit as a power of ten of the base unit
+/// of that quantity, for example 3 for kilowatts.  Representations
+/// of the same quantity can be rescaled exactly, provided their
+/// prefixes differ by no more than 18.
 ///
 /// The trait requirements ensure those same traits
 /// can be sucessfully derived for every FixedPoint type.
@@ -53,12 +234,17 @@ pub trait Spec
 where
     Self: Clone + Copy + Eq + PartialEq + Serialize + for<'a> Deserialize<'a>,
 {
-    const SCALE: Float;
+    type Quantity;
+    const DENOM: Wide;
+    const PREFIX: i32 = 0;
+    #[cfg(feature = "float")]
+    const SCALE: Float = Self::DENOM as Float;
     const SYMBOL: &'static str;
     fn to_fixed(self) -> Fixed;
     fn from_fixed(fixed: Fixed) -> Self;
 }
 
+#[cfg(feature = "float")]
 impl<R> FixedPoint<R>
 where
     R: Spec,
@@ -74,12 +260,78 @@ where
     }
 }
 
+impl<R> FixedPoint<R>
+where
+    R: Spec,
+{
+    /// Exact conversion to another representation of the same quantity
+    /// using integer arithmetic.  The result is rounded to the nearest
+    /// value and saturates at the limits of the representation.
+    pub fn rescale<S>(self) -> FixedPoint<S>
+    where
+        S: Spec<Quantity = R::Quantity>,
+    {
+        FixedPoint(S::from_fixed(saturate(convert::<R, S>(self.0.to_fixed()))))
+    }
+
+    /// The sum with a value in another representation of the same quantity,
+    /// given in representation `T`.  Each operand is rounded to `T`, so the
+    /// sum is exact when `T` is at least as fine as both.  The result saturates.
+    pub fn add_as<T>(self, rhs: FixedPoint<impl Spec<Quantity = R::Quantity>>) -> FixedPoint<T>
+    where
+        T: Spec<Quantity = R::Quantity>,
+    {
+        let lhs = self.convert_to::<T>();
+        FixedPoint(T::from_fixed(saturate(
+            lhs.saturating_add(rhs.convert_to::<T>()),
+        )))
+    }
+
+    /// The difference with a value in another representation of the same
+    /// quantity, given in representation `T` and rounded as for `add_as`.
+    pub fn sub_as<T>(self, rhs: FixedPoint<impl Spec<Quantity = R::Quantity>>) -> FixedPoint<T>
+    where
+        T: Spec<Quantity = R::Quantity>,
+    {
+        let lhs = self.convert_to::<T>();
+        FixedPoint(T::from_fixed(saturate(
+            lhs.saturating_sub(rhs.convert_to::<T>()),
+        )))
+    }
+
+    fn convert_to<T>(self) -> Wide
+    where
+        T: Spec,
+    {
+        convert::<R, T>(self.0.to_fixed())
+    }
+}
+
+impl<R> FixedPoint<R>
+where
+    R: Spec<Quantity = quantity::Dimensionless>,
+{
+    /// The square root of a dimensionless value, rounded to nearest,
+    /// or None if self is negative.  This uses integer arithmetic only.
+    pub fn sqrt(self) -> Option<Self> {
+        let fixed = u64::try_from(self.0.to_fixed()).ok()? as u128;
+        let power = 10u128.pow(R::PREFIX.unsigned_abs());
+        let square = if R::PREFIX <= 0 {
+            fixed * R::DENOM as u128 * power
+        } else {
+            (fixed * R::DENOM as u128 + power / 2) / power
+        };
+        let root = sqrt_round(square.min(u64::MAX as u128) as u64);
+        Some(Self(R::from_fixed(saturate(root as Wide))))
+    }
+}
+
 impl<R> fmt::Debug for FixedPoint<R>
 where
     R: Spec,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}/{} {}", self.0.to_fixed(), R::SCALE, R::SYMBOL)
+        write!(f, "{:?}/{} {}", self.0.to_fixed(), R::DENOM, R::SYMBOL)
     }
 }
 
@@ -89,7 +341,7 @@ where
     R: Spec,
 {
     fn format(&self, f: defmt::Formatter) {
-        defmt::write!(f, "{}/{} {}", self.0.to_fixed(), R::SCALE, R::SYMBOL)
+        defmt::write!(f, "{}/{} {}", self.0.to_fixed(), R::DENOM, R::SYMBOL)
     }
 }
 
@@ -98,40 +350,48 @@ where
     R: Spec,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // special treat ment for these decimal point scales
-        if R::SCALE == 10.0 || R::SCALE == 100.0 || R::SCALE == 1000.0 {
-            let fixed = self.0.to_fixed();
-            let sign = if fixed < 0 { "-" } else { "" };
-            let magn = fixed.abs();
-            let whole = magn / R::SCALE as Fixed;
-            let frac = magn % R::SCALE as Fixed;
-
-            if frac > 0 {
-                if R::SCALE == 10.0 {
-                    write!(f, "{sign}{whole}.{frac}")
-                } else if R::SCALE == 100.0 {
-                    if frac % 10 == 0 {
-                        write!(f, "{sign}{whole}.{}", frac / 10)
-                    } else {
-                        write!(f, "{sign}{whole}.{:02}", frac)
-                    }
-                } else if frac % 100 == 0 {
-                    write!(f, "{sign}{whole}.{}", frac / 100)
-                } else if frac % 10 == 0 {
-                    write!(f, "{sign}{whole}.{:02}", frac / 10)
-                } else {
-                    write!(f, "{sign}{whole}.{:03}", frac)
+        let fixed = self.0.to_fixed() as Wide;
+        let sign = if fixed < 0 { "-" } else { "" };
+        let whole = fixed.abs() / R::DENOM;
+        let mut rem = fixed.abs() % R::DENOM;
+
+        // generate enough decimal places to distinguish every value
+        let mut digits = [0u8; 19];
+        let mut places = 0;
+        let mut unit: Wide = 1;
+        while unit < R::DENOM {
+            rem *= 10;
+            digits[places] = (rem / R::DENOM) as u8;
+            rem %= R::DENOM;
+            places += 1;
+            unit *= 10;
+        }
+
+        // round the last place (only needed for non decimal scales)
+        // which never carries into the whole part because unit >= DENOM
+        if 2 * rem >= R::DENOM {
+            for digit in digits[..places].iter_mut().rev() {
+                if *digit < 9 {
+                    *digit += 1;
+                    break;
                 }
-            } else {
-                write!(f, "{sign}{whole}")
+                *digit = 0;
+            }
+        }
+
+        // trailing zeros are not displayed
+        while places > 0 && digits[places - 1] == 0 {
+            places -= 1;
+        }
+
+        write!(f, "{sign}{whole}")?;
+        if places > 0 {
+            f.write_str(".")?;
+            for digit in &digits[..places] {
+                write!(f, "{digit}")?;
             }
-        } else if R::SCALE == 1.0 {
-            // scale 1.0 is displayed without conversions
-            write!(f, "{}", self.0.to_fixed())
-        } else {
-            // Every other scale including non decimal
-            write!(f, "{}", self.to_float())
         }
+        Ok(())
     }
 }
 
@@ -144,15 +404,48 @@ where
 {
     type Err = ParseError;
 
+    /// Parse a decimal number without floating point.
+    /// Excess decimal places are truncated and values
+    /// outside the range of the representation saturate.
     fn from_str(text: &str) -> Result<Self, Self::Err> {
-        if let Ok(value) = text.parse::<Float>() {
-            Ok(value.into())
+        let (negative, text) = if let Some(text) = text.strip_prefix('-') {
+            (true, text)
         } else {
-            Err(ParseError)
+            (false, text.strip_prefix('+').unwrap_or(text))
+        };
+        let (whole, frac) = text.split_once('.').unwrap_or((text, ""));
+        if whole.is_empty() && frac.is_empty()
+            || !whole
+                .bytes()
+                .chain(frac.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return Err(ParseError);
         }
+
+        let mut fixed: Wide = 0;
+        for b in whole.bytes() {
+            fixed = fixed.saturating_mul(10).saturating_add((b - b'0') as Wide);
+        }
+        fixed = fixed.saturating_mul(R::DENOM);
+
+        // places beyond the range of Wide arithmetic make no difference
+        let mut numer: Wide = 0;
+        let mut denom: Wide = 1;
+        for b in frac.bytes().take(9) {
+            numer = numer * 10 + (b - b'0') as Wide;
+            denom *= 10;
+        }
+        fixed = fixed.saturating_add(numer * R::DENOM / denom);
+
+        if negative {
+            fixed = -fixed;
+        }
+        Ok(Self(R::from_fixed(saturate(fixed))))
     }
 }
 
+#[cfg(feature = "float")]
 impl<R> From<Float> for FixedPoint<R>
 where
     R: Spec,
@@ -162,6 +455,7 @@ where
     }
 }
 
+#[cfg(feature = "float")]
 impl<R> From<FixedPoint<R>> for Float
 where
     R: Spec,
@@ -188,7 +482,7 @@ impl<R> AddAssign<FixedPoint<R>> for FixedPoint<R>
 where
     R: Spec,
 {
-    fn add_assign(&mut self, rhs: FixedPoint<R>) {
+    fn add_assign(&mut self, rhs: Self) {
         *self = *self + rhs;
     }
 }
@@ -210,11 +504,23 @@ impl<R> SubAssign<FixedPoint<R>> for FixedPoint<R>
 where
     R: Spec,
 {
-    fn sub_assign(&mut self, rhs: FixedPoint<R>) {
+    fn sub_assign(&mut self, rhs: Self) {
         *self = *self - rhs;
     }
 }
 
+impl<R> Neg for FixedPoint<R>
+where
+    R: Spec,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(R::from_fixed(self.0.to_fixed().saturating_neg()))
+    }
+}
+
+#[cfg(feature = "float")]
 impl<R> Mul<Float> for FixedPoint<R>
 where
     R: Spec,
@@ -227,6 +533,7 @@ where
     }
 }
 
+#[cfg(feature = "float")]
 impl<R> MulAssign<Float> for FixedPoint<R>
 where
     R: Spec,
@@ -236,6 +543,7 @@ where
     }
 }
 
+#[cfg(feature = "float")]
 impl<R> Div<Float> for FixedPoint<R>
 where
     R: Spec,
@@ -248,28 +556,99 @@ where
     }
 }
 
-impl<R> Div<FixedPoint<R>> for FixedPoint<R>
+#[cfg(feature = "float")]
+impl<R> DivAssign<Float> for FixedPoint<R>
+where
+    R: Spec,
+{
+    fn div_assign(&mut self, rhs: Float) {
+        *self = *self / rhs;
+    }
+}
+
+/// Exact comparison of values in different representations of the same quantity.
+impl<R, S> PartialEq<FixedPoint<S>> for FixedPoint<R>
 where
     R: Spec,
+    S: Spec<Quantity = R::Quantity>,
 {
-    type Output = Float;
+    fn eq(&self, other: &FixedPoint<S>) -> bool {
+        compare(*self, *other) == Ordering::Equal
+    }
+}
 
-    fn div(self, rhs: Self) -> Self::Output {
-        let lhs: Float = self.into();
-        let rhs: Float = rhs.into();
-        lhs / rhs
+impl<R> Eq for FixedPoint<R> where R: Spec {}
+
+impl<R, S> PartialOrd<FixedPoint<S>> for FixedPoint<R>
+where
+    R: Spec,
+    S: Spec<Quantity = R::Quantity>,
+{
+    fn partial_cmp(&self, other: &FixedPoint<S>) -> Option<Ordering> {
+        Some(compare(*self, *other))
     }
 }
 
-impl<R> DivAssign<Float> for FixedPoint<R>
+impl<R> Ord for FixedPoint<R>
 where
     R: Spec,
 {
-    fn div_assign(&mut self, rhs: Float) {
-        *self = *self / rhs;
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare(*self, *other)
+    }
+}
+
+fn compare<R, S>(lhs: FixedPoint<R>, rhs: FixedPoint<S>) -> Ordering
+where
+    R: Spec,
+    S: Spec<Quantity = R::Quantity>,
+{
+    let (lhs, rhs) = (lhs.0.to_fixed(), rhs.0.to_fixed());
+    match conversion::<R, S>() {
+        (1, 1) => lhs.cmp(&rhs),
+        (numer, denom) => (lhs as i128 * numer).cmp(&(rhs as i128 * denom)),
     }
 }
 
+/// The product of values of two quantities in the representation of a third,
+/// rounded to nearest and saturating.
+fn product<A, B, C>(lhs: FixedPoint<A>, rhs: FixedPoint<B>) -> FixedPoint<C>
+where
+    A: Spec,
+    B: Spec,
+    C: Spec,
+{
+    let numer = lhs.0.to_fixed() as i128 * rhs.0.to_fixed() as i128 * C::DENOM as i128;
+    let denom = A::DENOM as i128 * B::DENOM as i128;
+    let value = div_round(numer, denom).clamp(Fixed::MIN as i128, Fixed::MAX as i128);
+    FixedPoint(C::from_fixed(value as Fixed))
+}
+
+/// Multiplication of two quantities, in either order, giving a third
+/// using integer arithmetic.
+macro_rules! product {
+    ($lhs:ty, $rhs:ty, $output:ty) => {
+        impl Mul<FixedPoint<$rhs>> for FixedPoint<$lhs> {
+            type Output = FixedPoint<$output>;
+
+            fn mul(self, rhs: FixedPoint<$rhs>) -> Self::Output {
+                product(self, rhs)
+            }
+        }
+
+        impl Mul<FixedPoint<$lhs>> for FixedPoint<$rhs> {
+            type Output = FixedPoint<$output>;
+
+            fn mul(self, rhs: FixedPoint<$lhs>) -> Self::Output {
+                product(rhs, self)
+            }
+        }
+    };
+}
+
+product!(unit::Volt, unit::Amp, unit::Watt);
+product!(unit::PreciseVolt, unit::Amp, unit::Watt);
+
 // constants and const constructors for the representations defined in unit
 impl FixedPoint<unit::Amp> {
     pub const ZERO: Self = Self::with_fix1(0);
@@ -355,11 +734,12 @@ impl FixedPoint<unit::KiloWattHour> {
     }
 }
 
-// special case energy conversion
+// special case energy conversion, truncated toward zero
+// (use rescale for a rounded conversion)
 impl From<FixedPoint<unit::Watt>> for FixedPoint<unit::KiloWatt> {
     fn from(value: FixedPoint<unit::Watt>) -> Self {
-        let watts: Float = value.into();
-        (watts * 0.001).into()
+        let fixed = value.fix0() as Wide * unit::KiloWatt::DENOM;
+        Self::with_fix1(saturate(fixed / (1000 * unit::Watt::DENOM)))
     }
 }
 
@@ -370,6 +750,20 @@ impl FixedPoint<unit::Watt> {
     }
 }
 
+impl FixedPoint<unit::Percent> {
+    pub const ZERO: Self = Self::with_fix1(0);
+
+    /// Construct from a integer interpreted at 10x scale.
+    pub const fn with_fix1(value: Fixed) -> Self {
+        Self(unit::Percent(value))
+    }
+
+    /// Extract an integer at 10x scale
+    pub const fn fix1(self) -> Fixed {
+        self.0 .0
+    }
+}
+
 impl FixedPoint<unit::Celsius> {
     pub const ZERO: Self = Self::with_fix2(0);
 
@@ -384,6 +778,34 @@ impl FixedPoint<unit::Celsius> {
     }
 }
 
+impl FixedPoint<unit::Degree> {
+    pub const ZERO: Self = Self::with_fix2(0);
+
+    /// Construct from a integer interpreted at 100x scale.
+    pub const fn with_fix2(value: Fixed) -> Self {
+        Self(unit::Degree(value))
+    }
+
+    /// Extract an integer at 100x scale
+    pub const fn fix2(self) -> Fixed {
+        self.0 .0
+    }
+}
+
+impl FixedPoint<unit::Ohm> {
+    pub const ZERO: Self = Self::with_fix2(0);
+
+    /// Construct from a integer interpreted at 100x scale.
+    pub const fn with_fix2(value: Fixed) -> Self {
+        Self(unit::Ohm(value))
+    }
+
+    /// Extract an integer at 100x scale
+    pub const fn fix2(self) -> Fixed {
+        self.0 .0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -395,6 +817,7 @@ mod tests {
     type LowVoltage = FixedPoint<unit::PreciseVolt>;
 
     #[test]
+    #[cfg(feature = "float")]
     fn cloning_and_equality() {
         let e1: Energy = 5.01f32.into();
         #[allow(clippy::clone_on_copy)]
@@ -403,6 +826,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "float")]
     fn ordering() {
         let e1: Energy = 5.01f32.into();
         let e2: Energy = 5.11f32.into();
@@ -410,18 +834,21 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "float")]
     fn serialization() {
         let e1: Energy = 5.01f32.into();
         assert_eq!(serde_json::to_string(&e1).unwrap(), "501");
     }
 
     #[test]
+    #[cfg(feature = "float")]
     fn construction() {
         assert_eq!(LowVoltage::new(1.705).to_float(), 1.705);
         assert_eq!(LowVoltage::with_fix3(1705).to_float(), 1.705);
     }
 
     #[test]
+    #[cfg(feature = "float")]
     fn fixing() {
         assert_eq!(LowVoltage::new(1.705).fix3(), 1705);
         assert_eq!(Energy::new(1.705).fix2(), 170);
@@ -429,6 +856,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "float")]
     fn display() {
         let e1: Energy = 5.01f32.into();
         assert_eq!(format!("{}", e1), "5.01");
@@ -478,6 +906,146 @@ mod tests {
         assert_eq!("0.".parse(), Ok(Current::with_fix1(0)));
     }
 
+    #[test]
+    fn test_parse_exact() {
+        assert_eq!("+3".parse(), Ok(Current::with_fix1(30)));
+        assert_eq!("0.0999".parse(), Ok(Current::with_fix1(0)));
+        assert_eq!("1.705".parse(), Ok(LowVoltage::with_fix3(1705)));
+        assert_eq!("99999999999".parse(), Ok(Current::with_fix1(Fixed::MAX)));
+        assert_eq!("0.5".parse(), Ok(FixedPoint::<unit::Q15>::with_q15(16384)));
+        assert_eq!(".".parse::<Current>(), Err(ParseError));
+        assert_eq!("-".parse::<Current>(), Err(ParseError));
+        assert_eq!("1e3".parse::<Current>(), Err(ParseError));
+        assert_eq!("1.2.3".parse::<Current>(), Err(ParseError));
+        assert_eq!(" 1".parse::<Current>(), Err(ParseError));
+    }
+
+    #[test]
+    fn test_display_binary() {
+        type Fraction = FixedPoint<unit::Q15>;
+        assert_eq!(Fraction::with_q15(16384).to_string(), "0.5");
+        assert_eq!(Fraction::with_q15(-1).to_string(), "-0.00003");
+        assert_eq!(Fraction::MAX.to_string(), "0.99997");
+        assert_eq!(Fraction::MIN.to_string(), "-1");
+        assert_eq!(
+            FixedPoint::<unit::Q16_16>::with_q16(21845).to_string(),
+            "0.33333"
+        );
+    }
+
+    #[test]
+    fn test_debug() {
+        assert_eq!(format!("{:?}", Energy::with_fix2(501)), "501/100 kWh");
+    }
+
+    #[test]
+    fn test_mixed_scales() {
+        let cell = LowVoltage::with_fix3(3305);
+        let pack = Voltage::with_fix1(33);
+        assert!(cell > pack);
+        assert!(pack < cell);
+        assert!(LowVoltage::with_fix3(3300) == pack);
+        assert!(pack != LowVoltage::with_fix3(3301));
+        // the sum is exact in either order in the finer representation
+        let sum = LowVoltage::with_fix3(6605);
+        assert_eq!(cell.add_as::<unit::PreciseVolt>(pack), sum);
+        assert_eq!(pack.add_as::<unit::PreciseVolt>(cell), sum);
+        assert_eq!(
+            pack.sub_as::<unit::PreciseVolt>(cell),
+            LowVoltage::with_fix3(-5)
+        );
+        // a coarser representation rounds each operand
+        assert_eq!(pack.add_as::<unit::Volt>(cell), Voltage::with_fix1(66));
+        assert_eq!(sum.rescale::<unit::Volt>(), Voltage::with_fix1(66));
+
+        let rate = FixedPoint::<unit::KiloWatt>::with_fix1(74);
+        assert!(rate == Power::with_fix0(7400));
+        assert!(rate < Power::with_fix0(7401));
+        assert_eq!(
+            Power::with_fix0(100).add_as::<unit::Watt>(rate),
+            Power::with_fix0(7500)
+        );
+    }
+
+    #[test]
+    fn test_rescale_prefix() {
+        type Rate = FixedPoint<unit::KiloWatt>;
+        assert_eq!(
+            Power::with_fix0(1550).rescale::<unit::KiloWatt>(),
+            Rate::with_fix1(16)
+        );
+        assert_eq!(
+            Power::with_fix0(-1450).rescale::<unit::KiloWatt>(),
+            Rate::with_fix1(-15)
+        );
+        assert_eq!(
+            Rate::with_fix1(74).rescale::<unit::Watt>(),
+            Power::with_fix0(7400)
+        );
+        assert_eq!(
+            FixedPoint::<unit::Percent>::with_fix1(125).rescale::<unit::Ratio>(),
+            ratio::Ratio::with_fix4(1250)
+        );
+        assert_eq!(
+            Rate::with_fix1(Fixed::MAX).rescale::<unit::Watt>(),
+            Power::with_fix0(Fixed::MAX)
+        );
+    }
+
+    #[test]
+    fn test_power() {
+        assert_eq!(
+            Voltage::with_fix1(2304) * Current::with_fix1(321),
+            Power::with_fix0(7396)
+        );
+        assert_eq!(
+            Current::with_fix1(-5) * Voltage::with_fix1(2301),
+            Power::with_fix0(-115)
+        );
+        assert_eq!(
+            LowVoltage::with_fix3(3305) * Current::with_fix1(1000),
+            Power::with_fix0(331)
+        );
+        assert_eq!(
+            Voltage::with_fix1(i32::MAX) * Current::with_fix1(100),
+            Power::with_fix0(i32::MAX)
+        );
+    }
+
+    #[test]
+    fn test_sqrt() {
+        use ratio::Ratio;
+        type Percent = FixedPoint<unit::Percent>;
+        assert_eq!(
+            Ratio::with_fix4(20000).sqrt(),
+            Some(Ratio::with_fix4(14142))
+        );
+        assert_eq!(Ratio::ZERO.sqrt(), Some(Ratio::ZERO));
+        assert_eq!(Ratio::with_fix4(-1).sqrt(), None);
+        assert_eq!(
+            Percent::with_fix1(500).sqrt(),
+            Some(Percent::with_fix1(707))
+        );
+        let half = FixedPoint::<unit::Q15>::with_q15(1 << 14);
+        assert_eq!(half.sqrt().unwrap().q15(), 23170);
+        assert_eq!(
+            FixedPoint::<unit::Q31>::MAX.sqrt(),
+            Some(FixedPoint::<unit::Q31>::MAX)
+        );
+        for fixed in (0..i32::MAX).step_by(9_999_991) {
+            let reference = (fixed as f64 / 10000.0).sqrt() * 10000.0;
+            let root = Ratio::with_fix4(fixed).sqrt().unwrap().fix4();
+            assert!((root as f64 - reference).abs() <= 0.5);
+        }
+        for square in [2, 3, 4, 99, 1 << 40, u64::MAX - 1, u64::MAX] {
+            let root = sqrt_floor(square);
+            assert!(root * root <= square);
+            assert!((root + 1)
+                .checked_mul(root + 1)
+                .map_or(true, |next| next > square));
+        }
+    }
+
     #[test]
     fn test_display_energy() {
         assert_eq!(Energy::with_fix2(305).to_string(), "3.05");
@@ -496,6 +1064,9 @@ mod tests {
         assert_eq!(Power::with_fix0(1).kwh().to_string(), "0");
         assert_eq!(Power::with_fix0(114).kwh().to_string(), "0.1");
         assert_eq!(Power::with_fix0(14).kwh().to_string(), "0");
+        assert_eq!(Power::with_fix0(150).kwh().to_string(), "0.1");
+        assert_eq!(Power::with_fix0(199).kwh().to_string(), "0.1");
+        assert_eq!(Power::with_fix0(-199).kwh().to_string(), "-0.1");
     }
 
     #[test]
@@ -0,0 +1,191 @@
+use core::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{div_round, saturate, Fixed, FixedPoint, Spec, Wide};
+
+/// A representation for signals encoded on the wire as `raw * FACTOR + OFFSET`,
+/// as is common for CAN and Modbus signals.
+///
+/// The physical value is given by spec `U`.  `FACTOR` and `OFFSET` are
+/// expressed in the fixed point units of `U` and `W` is the raw integer type.
+/// `FACTOR` must be positive, which is checked when the spec is used.
+///
+/// For example, a temperature in a u8 offset by -40 °C in 1 °C steps is
+/// `Offset<unit::Celsius, u8, 100, -4000>` and a state of charge in 0.4 % steps
+/// is `Offset<unit::Percent, u8, 4, 0>`.
+///
+/// A `FixedPoint<Offset<..>>` serializes as the raw encoded value while
+/// arithmetic, ordering and display work in the physical domain.
+/// Values are rounded to the nearest step and saturate at the limits of `W`.
+/// The default is the encoding of physical zero, saturated if zero is out of range.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[serde(transparent)]
+pub struct Offset<U, W, const FACTOR: Fixed, const OFFSET: Fixed>(
+    pub W,
+    #[serde(skip)] PhantomData<U>,
+);
+
+impl<U, W, const FACTOR: Fixed, const OFFSET: Fixed> Spec for Offset<U, W, FACTOR, OFFSET>
+where
+    U: Spec,
+    W: Word,
+{
+    type Quantity = U::Quantity;
+    const DENOM: Wide = U::DENOM;
+    const PREFIX: i32 = U::PREFIX;
+    const SYMBOL: &'static str = U::SYMBOL;
+
+    fn to_fixed(self) -> Fixed {
+        const { assert!(FACTOR > 0, "an offset factor must be positive") };
+        saturate(self.0.to_wide() * FACTOR as Wide + OFFSET as Wide)
+    }
+    fn from_fixed(fixed: Fixed) -> Self {
+        const { assert!(FACTOR > 0, "an offset factor must be positive") };
+        let raw = div_round(fixed as Wide - OFFSET as Wide, FACTOR as Wide);
+        Self(W::from_wide(raw), PhantomData)
+    }
+}
+
+impl<U, W, const FACTOR: Fixed, const OFFSET: Fixed> Default for Offset<U, W, FACTOR, OFFSET>
+where
+    U: Spec,
+    W: Word,
+{
+    fn default() -> Self {
+        Self::from_fixed(0)
+    }
+}
+
+impl<U, W, const FACTOR: Fixed, const OFFSET: Fixed> FixedPoint<Offset<U, W, FACTOR, OFFSET>>
+where
+    U: Spec,
+    W: Word,
+{
+    /// Construct from the raw encoded value.
+    pub const fn with_raw(raw: W) -> Self {
+        Self(Offset(raw, PhantomData))
+    }
+
+    /// Extract the raw encoded value.
+    pub fn raw(self) -> W {
+        self.0 .0
+    }
+
+    /// Encode a physical value, rounding to the nearest step.
+    pub fn encode(value: FixedPoint<U>) -> Self {
+        Self(Spec::from_fixed(value.0.to_fixed()))
+    }
+
+    /// Decode to the physical value.  This is exact.
+    pub fn decode(self) -> FixedPoint<U> {
+        FixedPoint(U::from_fixed(self.0.to_fixed()))
+    }
+}
+
+impl<U, W, const FACTOR: Fixed, const OFFSET: Fixed> From<FixedPoint<Offset<U, W, FACTOR, OFFSET>>>
+    for FixedPoint<U>
+where
+    U: Spec,
+    W: Word,
+{
+    fn from(value: FixedPoint<Offset<U, W, FACTOR, OFFSET>>) -> Self {
+        value.decode()
+    }
+}
+
+impl<U, W, const FACTOR: Fixed, const OFFSET: Fixed> From<FixedPoint<U>>
+    for FixedPoint<Offset<U, W, FACTOR, OFFSET>>
+where
+    U: Spec,
+    W: Word,
+{
+    fn from(value: FixedPoint<U>) -> Self {
+        Self::encode(value)
+    }
+}
+
+/// An integer type that can carry a raw encoded value.
+pub trait Word
+where
+    Self: Copy + Default + Ord + Serialize + for<'a> Deserialize<'a>,
+{
+    fn to_wide(self) -> Wide;
+    /// Conversion that saturates at the limits of the type.
+    fn from_wide(value: Wide) -> Self;
+}
+
+macro_rules! impl_word {
+    ($($word:ty),*) => {
+        $(
+            impl Word for $word {
+                fn to_wide(self) -> Wide {
+                    self as Wide
+                }
+                fn from_wide(value: Wide) -> Self {
+                    value.clamp(<$word>::MIN as Wide, <$word>::MAX as Wide) as $word
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(u8, i8, u16, i16, u32, i32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unit;
+
+    type CellTemp = FixedPoint<Offset<unit::Celsius, u8, 100, -4000>>;
+    type Charge = FixedPoint<Offset<unit::Percent, u8, 4, 0>>;
+    type Temperature = FixedPoint<unit::Celsius>;
+
+    #[test]
+    fn decoding() {
+        assert_eq!(
+            CellTemp::with_raw(0).decode(),
+            Temperature::with_fix2(-4000)
+        );
+        assert_eq!(
+            CellTemp::with_raw(65).decode(),
+            Temperature::with_fix2(2500)
+        );
+        assert_eq!(Charge::with_raw(201).to_string(), "80.4");
+    }
+
+    #[test]
+    fn encoding() {
+        assert_eq!(CellTemp::encode(Temperature::with_fix2(2549)).raw(), 65);
+        assert_eq!(CellTemp::encode(Temperature::with_fix2(2550)).raw(), 66);
+        assert_eq!(CellTemp::encode(Temperature::with_fix2(-5000)).raw(), 0);
+        assert_eq!(CellTemp::encode(Temperature::with_fix2(30000)).raw(), 255);
+        assert_eq!("80.4".parse::<Charge>().unwrap().raw(), 201);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let t = CellTemp::with_raw(60) + CellTemp::with_raw(45);
+        assert_eq!(t.decode(), Temperature::with_fix2(2500));
+        assert_eq!(t.raw(), 65);
+        assert!(CellTemp::with_raw(41) > CellTemp::with_raw(40));
+    }
+
+    #[test]
+    fn default_is_zero() {
+        assert_eq!(CellTemp::default().decode(), Temperature::ZERO);
+        assert_eq!(CellTemp::default().raw(), 40);
+        assert_eq!(Charge::default().raw(), 0);
+        // zero is below the range of an encoding offset by +10 C
+        type Warm = FixedPoint<Offset<unit::Celsius, u8, 100, 1000>>;
+        assert_eq!(Warm::default().raw(), 0);
+    }
+
+    #[test]
+    fn serialization() {
+        let t = CellTemp::with_raw(65);
+        assert_eq!(serde_json::to_string(&t).unwrap(), "65");
+        assert_eq!(serde_json::from_str::<CellTemp>("65").unwrap(), t);
+    }
+}
@@ -1,108 +1,1163 @@
-use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+#[cfg(feature = "float")]
+use core::ops::{Div, DivAssign};
+use core::{
+    array, fmt,
+    iter::{Enumerate, Zip},
+    marker::PhantomData,
+    ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign},
+};
 
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 
+#[cfg(feature = "float")]
 use crate::Float;
+use crate::{div_round, ratio::Ratio, saturate, unit, FixedPoint, Spec, Wide};
 
 /// Represents a three phase electrical quanity where some or all
 /// phases may be out of service. (An out of service phase is
 /// distinct from a phase with a zero value.)
+///
+/// The combinators, aggregates and arithmetic are those of
+/// `PhasesN<T, 3>`, to which a `PhasesOpt` converts freely.
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct PhasesOpt<T>(pub Option<T>, pub Option<T>, pub Option<T>);
 
+/// One of the three phases.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Phase {
+    L1,
+    L2,
+    L3,
+}
+
+impl Phase {
+    /// The phases in order.
+    pub const ALL: [Phase; 3] = [Phase::L1, Phase::L2, Phase::L3];
+}
+
+impl<T> PhasesOpt<T> {
+    pub fn count(&self) -> usize {
+        self.0.is_some() as usize + self.1.is_some() as usize + self.2.is_some() as usize
+    }
+
+    /// Construct from a function giving the value of each phase.
+    pub fn from_fn<F>(mut f: F) -> Self
+    where
+        F: FnMut(Phase) -> Option<T>,
+    {
+        Self(f(Phase::L1), f(Phase::L2), f(Phase::L3))
+    }
+
+    /// Iterate over the phases in service.
+    pub fn iter(&self) -> IntoIter<&T> {
+        self.into_iter()
+    }
+
+    /// Iterate mutably over the phases in service.
+    pub fn iter_mut(&mut self) -> IntoIter<&mut T> {
+        self.into_iter()
+    }
+}
+
+/// How to combine two `PhasesOpt` when a phase is in service in only one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Policy {
+    /// The missing value is taken as the default (zero) so the phase is in service
+    /// if it is in either.  This suits summing contributions.
+    Union,
+    /// The phase is out of service unless it is in both.
+    /// This suits comparing measurements.
+    Intersection,
+}
+
+impl<T> PhasesOpt<T> {
+    /// Apply a function to the value of each phase in service.
+    pub fn map<U, F>(self, f: F) -> PhasesOpt<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        PhasesN::from(self).map(f).into()
+    }
+
+    /// Apply a function to the value of each phase in service, which
+    /// may take the phase out of service.
+    pub fn and_then<U, F>(self, f: F) -> PhasesOpt<U>
+    where
+        F: FnMut(T) -> Option<U>,
+    {
+        PhasesN::from(self).and_then(f).into()
+    }
+
+    /// Take out of service each phase whose value does not satisfy the predicate.
+    pub fn filter<P>(self, predicate: P) -> Self
+    where
+        P: FnMut(&T) -> bool,
+    {
+        PhasesN::from(self).filter(predicate).into()
+    }
+
+    pub fn as_ref(&self) -> PhasesOpt<&T> {
+        PhasesOpt(self.0.as_ref(), self.1.as_ref(), self.2.as_ref())
+    }
+
+    pub fn as_mut(&mut self) -> PhasesOpt<&mut T> {
+        PhasesOpt(self.0.as_mut(), self.1.as_mut(), self.2.as_mut())
+    }
+
+    /// Combine with another `PhasesOpt` phase by phase.
+    /// The policy determines the result for a phase that is in service in only one.
+    pub fn zip_with<U, V, F>(self, other: PhasesOpt<U>, policy: Policy, f: F) -> PhasesOpt<V>
+    where
+        T: Default,
+        U: Default,
+        F: FnMut(T, U) -> V,
+    {
+        PhasesN::from(self).zip_with(other.into(), policy, f).into()
+    }
+}
+
+impl<T> PhasesOpt<T>
+where
+    T: Add<T, Output = T> + Default,
+{
+    /// Add phase by phase with the given policy for missing phases.
+    /// The `+` operator uses `Policy::Union`.
+    pub fn add_with(self, rhs: Self, policy: Policy) -> Self {
+        PhasesN::from(self).add_with(rhs.into(), policy).into()
+    }
+}
+
+impl<T> PhasesOpt<T>
+where
+    T: Sub<T, Output = T> + Default,
+{
+    /// Subtract phase by phase with the given policy for missing phases.
+    /// The `-` operator uses `Policy::Union`.
+    pub fn sub_with(self, rhs: Self, policy: Policy) -> Self {
+        PhasesN::from(self).sub_with(rhs.into(), policy).into()
+    }
+}
+
 impl<T> PhasesOpt<T> {
+    /// Wrap for arithmetic with `Policy::Intersection`.
+    pub fn strict(self) -> Strict<Self> {
+        Strict(self)
+    }
+}
+
+/// A `PhasesOpt` or `PhasesN` whose `+` and `-` operators use
+/// `Policy::Intersection`, so that a phase out of service in either
+/// operand is out of service in the result.  This suits element-wise
+/// comparison of measurements.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[serde(transparent)]
+pub struct Strict<P>(pub P);
+
+impl<P> From<P> for Strict<P> {
+    fn from(value: P) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> From<Strict<PhasesOpt<T>>> for PhasesOpt<T> {
+    fn from(value: Strict<PhasesOpt<T>>) -> Self {
+        value.0
+    }
+}
+
+impl<T, const N: usize> From<Strict<PhasesN<T, N>>> for PhasesN<T, N> {
+    fn from(value: Strict<PhasesN<T, N>>) -> Self {
+        value.0
+    }
+}
+
+impl<T, const N: usize> Add<Strict<PhasesN<T, N>>> for Strict<PhasesN<T, N>>
+where
+    T: Add<T, Output = T>,
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(PhasesN(zip_array(self.0 .0, rhs.0 .0, |a, b| {
+            both_opt(a, b, Add::add)
+        })))
+    }
+}
+
+impl<T, const N: usize> Sub<Strict<PhasesN<T, N>>> for Strict<PhasesN<T, N>>
+where
+    T: Sub<T, Output = T>,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(PhasesN(zip_array(self.0 .0, rhs.0 .0, |a, b| {
+            both_opt(a, b, Sub::sub)
+        })))
+    }
+}
+
+impl<T> Add<Strict<PhasesOpt<T>>> for Strict<PhasesOpt<T>>
+where
+    T: Add<T, Output = T>,
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let sum = PhasesN::from(self.0).strict() + PhasesN::from(rhs.0).strict();
+        Self(sum.0.into())
+    }
+}
+
+impl<T> Sub<Strict<PhasesOpt<T>>> for Strict<PhasesOpt<T>>
+where
+    T: Sub<T, Output = T>,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        let difference = PhasesN::from(self.0).strict() - PhasesN::from(rhs.0).strict();
+        Self(difference.0.into())
+    }
+}
+
+impl<P> AddAssign<Strict<P>> for Strict<P>
+where
+    Self: Add<Self, Output = Self> + Copy,
+{
+    fn add_assign(&mut self, rhs: Strict<P>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<P> SubAssign<Strict<P>> for Strict<P>
+where
+    Self: Sub<Self, Output = Self> + Copy,
+{
+    fn sub_assign(&mut self, rhs: Strict<P>) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T, E> PhasesOpt<Result<T, E>> {
+    /// Give the values if every phase in service is Ok, otherwise the first error.
+    pub fn transpose(self) -> Result<PhasesOpt<T>, E> {
+        Ok(PhasesN::from(self).transpose()?.into())
+    }
+}
+
+impl<T> Index<Phase> for PhasesOpt<T> {
+    type Output = Option<T>;
+
+    fn index(&self, phase: Phase) -> &Option<T> {
+        match phase {
+            Phase::L1 => &self.0,
+            Phase::L2 => &self.1,
+            Phase::L3 => &self.2,
+        }
+    }
+}
+
+impl<T> IndexMut<Phase> for PhasesOpt<T> {
+    fn index_mut(&mut self, phase: Phase) -> &mut Option<T> {
+        match phase {
+            Phase::L1 => &mut self.0,
+            Phase::L2 => &mut self.1,
+            Phase::L3 => &mut self.2,
+        }
+    }
+}
+
+impl<T> From<[Option<T>; 3]> for PhasesOpt<T> {
+    fn from([a, b, c]: [Option<T>; 3]) -> Self {
+        Self(a, b, c)
+    }
+}
+
+impl<T> From<PhasesOpt<T>> for [Option<T>; 3] {
+    fn from(value: PhasesOpt<T>) -> Self {
+        [value.0, value.1, value.2]
+    }
+}
+
+/// Collect phase and value pairs.  Phases not given are out of service
+/// and a phase given more than once takes the last value.
+impl<T> FromIterator<(Phase, T)> for PhasesOpt<T> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (Phase, T)>,
+    {
+        let mut phases = Self(None, None, None);
+        for (phase, value) in iter {
+            phases[phase] = Some(value);
+        }
+        phases
+    }
+}
+
+/// An iterator over the phases in service and their values.
+pub struct IntoIter<T>(Zip<array::IntoIter<Phase, 3>, array::IntoIter<Option<T>, 3>>);
+
+impl<T> IntoIter<T> {
+    fn new(values: [Option<T>; 3]) -> Self {
+        Self(Phase::ALL.into_iter().zip(values))
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = (Phase, T);
+
+    fn next(&mut self) -> Option<(Phase, T)> {
+        self.0
+            .find_map(|(phase, value)| value.map(|value| (phase, value)))
+    }
+}
+
+impl<T> IntoIterator for PhasesOpt<T> {
+    type Item = (Phase, T);
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter::new(self.into())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PhasesOpt<T> {
+    type Item = (Phase, &'a T);
+    type IntoIter = IntoIter<&'a T>;
+
+    fn into_iter(self) -> IntoIter<&'a T> {
+        IntoIter::new([self.0.as_ref(), self.1.as_ref(), self.2.as_ref()])
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut PhasesOpt<T> {
+    type Item = (Phase, &'a mut T);
+    type IntoIter = IntoIter<&'a mut T>;
+
+    fn into_iter(self) -> IntoIter<&'a mut T> {
+        IntoIter::new([self.0.as_mut(), self.1.as_mut(), self.2.as_mut()])
+    }
+}
+
+impl<T> PhasesOpt<T>
+where
+    T: Add<T, Output = T>,
+{
+    pub fn sum(self) -> Option<T> {
+        PhasesN::from(self).sum()
+    }
+}
+
+impl<T> PhasesOpt<T>
+where
+    T: Ord,
+{
+    pub fn max(self) -> Option<T> {
+        PhasesN::from(self).max()
+    }
+
+    pub fn min(self) -> Option<T> {
+        PhasesN::from(self).min()
+    }
+
+    /// The phase with the largest value and that value.
+    /// Ties go to the first phase.
+    pub fn argmax(self) -> Option<(Phase, T)> {
+        let (index, value) = PhasesN::from(self).argmax()?;
+        Some((Phase::ALL[index], value))
+    }
+
+    /// The phase with the smallest value and that value.
+    /// Ties go to the first phase.
+    pub fn argmin(self) -> Option<(Phase, T)> {
+        let (index, value) = PhasesN::from(self).argmin()?;
+        Some((Phase::ALL[index], value))
+    }
+}
+
+impl<T> PhasesOpt<T>
+where
+    T: Ord + Copy + Sub<T, Output = T>,
+{
+    /// The largest value less the smallest among the phases in service.
+    pub fn spread(self) -> Option<T> {
+        PhasesN::from(self).spread()
+    }
+}
+
+impl<R> PhasesOpt<FixedPoint<R>>
+where
+    R: Spec,
+{
+    /// The largest deviation of a phase from the average of the phases
+    /// in service, rounded to nearest.
+    pub fn deviation(self) -> Option<FixedPoint<R>> {
+        PhasesN::from(self).deviation()
+    }
+
+    /// The unbalance as defined by NEMA: the largest deviation from the
+    /// average as a proportion of the magnitude of the average.
+    /// None if no phase is in service or the average is zero.
+    ///
+    /// See `three_phase::LineToLine::unbalance_factor` for the IEC
+    /// definition in terms of sequence components.
+    pub fn unbalance(self) -> Option<Ratio> {
+        PhasesN::from(self).unbalance()
+    }
+}
+
+impl<T> Add<PhasesOpt<T>> for PhasesOpt<T>
+where
+    T: Add<T, Output = T>,
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        (PhasesN::from(self) + PhasesN::from(rhs)).into()
+    }
+}
+
+impl<T> AddAssign<PhasesOpt<T>> for PhasesOpt<T>
+where
+    T: Add<T, Output = T> + Copy,
+{
+    fn add_assign(&mut self, rhs: PhasesOpt<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T> Sub<PhasesOpt<T>> for PhasesOpt<T>
+where
+    T: Neg<Output = T> + Sub<T, Output = T>,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        (PhasesN::from(self) - PhasesN::from(rhs)).into()
+    }
+}
+
+impl<T> SubAssign<PhasesOpt<T>> for PhasesOpt<T>
+where
+    T: Neg<Output = T> + Sub<T, Output = T> + Copy,
+{
+    fn sub_assign(&mut self, rhs: PhasesOpt<T>) {
+        *self = *self - rhs;
+    }
+}
+
+/// Multiplication phase by phase, such as voltage by current giving power.
+/// A phase out of service in either operand is out of service in the result.
+impl<T, U> Mul<PhasesOpt<U>> for PhasesOpt<T>
+where
+    T: Mul<U>,
+{
+    type Output = PhasesOpt<T::Output>;
+
+    fn mul(self, rhs: PhasesOpt<U>) -> Self::Output {
+        (PhasesN::from(self) * PhasesN::from(rhs)).into()
+    }
+}
+
+#[cfg(feature = "float")]
+impl<T> Mul<Float> for PhasesOpt<T>
+where
+    T: Mul<Float, Output = T>,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Float) -> Self {
+        (PhasesN::from(self) * rhs).into()
+    }
+}
+
+#[cfg(feature = "float")]
+impl<T> MulAssign<Float> for PhasesOpt<T>
+where
+    T: Mul<Float, Output = T> + Copy,
+{
+    fn mul_assign(&mut self, rhs: Float) {
+        *self = *self * rhs;
+    }
+}
+
+#[cfg(feature = "float")]
+impl<T> Div<Float> for PhasesOpt<T>
+where
+    T: Mul<Float, Output = T>,
+{
+    type Output = Self;
+
+    fn div(self, rhs: Float) -> Self {
+        (PhasesN::from(self) / rhs).into()
+    }
+}
+
+#[cfg(feature = "float")]
+impl<T> DivAssign<Float> for PhasesOpt<T>
+where
+    T: Mul<Float, Output = T> + Copy,
+{
+    fn div_assign(&mut self, rhs: Float) {
+        *self = *self / rhs;
+    }
+}
+
+impl<T> Mul<Ratio> for PhasesOpt<T>
+where
+    T: Mul<Ratio, Output = T>,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Ratio) -> Self {
+        (PhasesN::from(self) * rhs).into()
+    }
+}
+
+impl<T> MulAssign<Ratio> for PhasesOpt<T>
+where
+    T: Mul<Ratio, Output = T> + Copy,
+{
+    fn mul_assign(&mut self, rhs: Ratio) {
+        *self = *self * rhs;
+    }
+}
+
+/// Represents a three phase quantity that is present on every phase,
+/// such as a configured limit or a nominal voltage.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Phases<T>(pub T, pub T, pub T);
+
+/// The error converting a `PhasesOpt` with a phase out of service to `Phases`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OutOfService(pub Phase);
+
+impl fmt::Display for OutOfService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "phase {:?} is out of service", self.0)
+    }
+}
+
+impl<T> Phases<T> {
+    /// The same value on every phase.
+    pub fn splat(value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self(value.clone(), value.clone(), value)
+    }
+
+    /// Construct from a function giving the value of each phase.
+    pub fn from_fn<F>(mut f: F) -> Self
+    where
+        F: FnMut(Phase) -> T,
+    {
+        Self(f(Phase::L1), f(Phase::L2), f(Phase::L3))
+    }
+
+    /// Apply a function to the value of each phase.
+    pub fn map<U, F>(self, mut f: F) -> Phases<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Phases(f(self.0), f(self.1), f(self.2))
+    }
+}
+
+impl<T> Phases<T>
+where
+    T: Add<T, Output = T>,
+{
+    pub fn sum(self) -> T {
+        self.0 + self.1 + self.2
+    }
+}
+
+impl<T> Phases<T>
+where
+    T: Ord,
+{
+    pub fn max(self) -> T {
+        self.0.max(self.1).max(self.2)
+    }
+
+    pub fn min(self) -> T {
+        self.0.min(self.1).min(self.2)
+    }
+}
+
+impl<T> Index<Phase> for Phases<T> {
+    type Output = T;
+
+    fn index(&self, phase: Phase) -> &T {
+        match phase {
+            Phase::L1 => &self.0,
+            Phase::L2 => &self.1,
+            Phase::L3 => &self.2,
+        }
+    }
+}
+
+impl<T> IndexMut<Phase> for Phases<T> {
+    fn index_mut(&mut self, phase: Phase) -> &mut T {
+        match phase {
+            Phase::L1 => &mut self.0,
+            Phase::L2 => &mut self.1,
+            Phase::L3 => &mut self.2,
+        }
+    }
+}
+
+impl<T> From<Phases<T>> for PhasesOpt<T> {
+    fn from(value: Phases<T>) -> Self {
+        Self(Some(value.0), Some(value.1), Some(value.2))
+    }
+}
+
+impl<T> TryFrom<PhasesOpt<T>> for Phases<T> {
+    type Error = OutOfService;
+
+    fn try_from(value: PhasesOpt<T>) -> Result<Self, OutOfService> {
+        Ok(Self(
+            value.0.ok_or(OutOfService(Phase::L1))?,
+            value.1.ok_or(OutOfService(Phase::L2))?,
+            value.2.ok_or(OutOfService(Phase::L3))?,
+        ))
+    }
+}
+
+impl<T> PhasesOpt<T> {
+    /// Fill the phases out of service with the given value.
+    pub fn unwrap_or(self, value: T) -> Phases<T>
+    where
+        T: Clone,
+    {
+        Phases(
+            self.0.unwrap_or_else(|| value.clone()),
+            self.1.unwrap_or_else(|| value.clone()),
+            self.2.unwrap_or(value),
+        )
+    }
+
+    /// Fill the phases out of service with the default (zero) value.
+    pub fn unwrap_or_default(self) -> Phases<T>
+    where
+        T: Default,
+    {
+        Phases(
+            self.0.unwrap_or_default(),
+            self.1.unwrap_or_default(),
+            self.2.unwrap_or_default(),
+        )
+    }
+}
+
+impl<T> Add<Phases<T>> for Phases<T>
+where
+    T: Add<T, Output = T>,
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+    }
+}
+
+impl<T> AddAssign<Phases<T>> for Phases<T>
+where
+    T: Add<T, Output = T> + Copy,
+{
+    fn add_assign(&mut self, rhs: Phases<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T> Sub<Phases<T>> for Phases<T>
+where
+    T: Sub<T, Output = T>,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0, self.1 - rhs.1, self.2 - rhs.2)
+    }
+}
+
+impl<T> SubAssign<Phases<T>> for Phases<T>
+where
+    T: Sub<T, Output = T> + Copy,
+{
+    fn sub_assign(&mut self, rhs: Phases<T>) {
+        *self = *self - rhs;
+    }
+}
+
+#[cfg(feature = "float")]
+impl<T> Mul<Float> for Phases<T>
+where
+    T: Mul<Float, Output = T>,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Float) -> Self {
+        Self(self.0 * rhs, self.1 * rhs, self.2 * rhs)
+    }
+}
+
+#[cfg(feature = "float")]
+impl<T> MulAssign<Float> for Phases<T>
+where
+    T: Mul<Float, Output = T> + Copy,
+{
+    fn mul_assign(&mut self, rhs: Float) {
+        *self = *self * rhs;
+    }
+}
+
+#[cfg(feature = "float")]
+impl<T> Div<Float> for Phases<T>
+where
+    T: Mul<Float, Output = T>,
+{
+    type Output = Self;
+
+    fn div(self, rhs: Float) -> Self {
+        self * (1.0 / rhs)
+    }
+}
+
+#[cfg(feature = "float")]
+impl<T> DivAssign<Float> for Phases<T>
+where
+    T: Mul<Float, Output = T> + Copy,
+{
+    fn div_assign(&mut self, rhs: Float) {
+        *self = *self / rhs;
+    }
+}
+
+impl<T> Mul<Ratio> for Phases<T>
+where
+    T: Mul<Ratio, Output = T>,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Ratio) -> Self {
+        Self(self.0 * rhs, self.1 * rhs, self.2 * rhs)
+    }
+}
+
+impl<T> MulAssign<Ratio> for Phases<T>
+where
+    T: Mul<Ratio, Output = T> + Copy,
+{
+    fn mul_assign(&mut self, rhs: Ratio) {
+        *self = *self * rhs;
+    }
+}
+
+/// Represents a quantity on `N` phases or conductors where some may be
+/// out of service, for wiring other than three phase.
+///
+/// The combinators, aggregates and arithmetic of `PhasesOpt` are defined
+/// here with phases identified by index, so that code written for
+/// `PhasesN` serves single, split and three phase wiring alike.
+/// `PhasesN<T, 3>` converts to and from `PhasesOpt` and has the same
+/// serialized form.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PhasesN<T, const N: usize>(pub [Option<T>; N]);
+
+/// A single phase or DC quantity.
+pub type SinglePhase<T> = PhasesN<T, 1>;
+
+/// A North American split phase quantity on L1 and L2.
+pub type SplitPhase<T> = PhasesN<T, 2>;
+
+impl<T, const N: usize> PhasesN<T, N> {
     pub fn count(&self) -> usize {
-        self.0.is_some() as usize + self.1.is_some() as usize + self.2.is_some() as usize
+        self.0.iter().filter(|value| value.is_some()).count()
+    }
+
+    /// Construct from a function giving the value of each phase by index.
+    pub fn from_fn<F>(f: F) -> Self
+    where
+        F: FnMut(usize) -> Option<T>,
+    {
+        Self(array::from_fn(f))
+    }
+
+    /// Iterate over the index and value of the phases in service.
+    pub fn iter(&self) -> IntoIterN<&T, N> {
+        self.into_iter()
+    }
+
+    /// Iterate mutably over the index and value of the phases in service.
+    pub fn iter_mut(&mut self) -> IntoIterN<&mut T, N> {
+        self.into_iter()
+    }
+
+    /// Apply a function to the value of each phase in service.
+    pub fn map<U, F>(self, mut f: F) -> PhasesN<U, N>
+    where
+        F: FnMut(T) -> U,
+    {
+        PhasesN(self.0.map(|value| value.map(&mut f)))
+    }
+
+    /// Apply a function to the value of each phase in service, which
+    /// may take the phase out of service.
+    pub fn and_then<U, F>(self, mut f: F) -> PhasesN<U, N>
+    where
+        F: FnMut(T) -> Option<U>,
+    {
+        PhasesN(self.0.map(|value| value.and_then(&mut f)))
+    }
+
+    /// Take out of service each phase whose value does not satisfy the predicate.
+    pub fn filter<P>(self, mut predicate: P) -> Self
+    where
+        P: FnMut(&T) -> bool,
+    {
+        Self(self.0.map(|value| value.filter(&mut predicate)))
+    }
+
+    pub fn as_ref(&self) -> PhasesN<&T, N> {
+        PhasesN(self.0.each_ref().map(Option::as_ref))
+    }
+
+    pub fn as_mut(&mut self) -> PhasesN<&mut T, N> {
+        PhasesN(self.0.each_mut().map(Option::as_mut))
+    }
+
+    /// Combine with another `PhasesN` phase by phase.
+    /// The policy determines the result for a phase that is in service in only one.
+    pub fn zip_with<U, V, F>(self, other: PhasesN<U, N>, policy: Policy, mut f: F) -> PhasesN<V, N>
+    where
+        T: Default,
+        U: Default,
+        F: FnMut(T, U) -> V,
+    {
+        PhasesN(zip_array(self.0, other.0, |a, b| {
+            zip_opt(a, b, policy, &mut f)
+        }))
+    }
+
+    /// Wrap for arithmetic with `Policy::Intersection`.
+    pub fn strict(self) -> Strict<Self> {
+        Strict(self)
+    }
+
+    /// The entry for a phase, where L1 is index 0,
+    /// or None if the phase is beyond the `N` phases.
+    pub fn get(&self, phase: Phase) -> Option<&Option<T>> {
+        self.0.get(phase as usize)
+    }
+
+    /// The mutable entry for a phase, where L1 is index 0,
+    /// or None if the phase is beyond the `N` phases.
+    pub fn get_mut(&mut self, phase: Phase) -> Option<&mut Option<T>> {
+        self.0.get_mut(phase as usize)
     }
 }
 
-impl<T> PhasesOpt<T>
+impl<T, const N: usize> PhasesN<T, N>
+where
+    T: Add<T, Output = T> + Default,
+{
+    /// Add phase by phase with the given policy for missing phases.
+    /// The `+` operator uses `Policy::Union`.
+    pub fn add_with(self, rhs: Self, policy: Policy) -> Self {
+        self.zip_with(rhs, policy, Add::add)
+    }
+}
+
+impl<T, const N: usize> PhasesN<T, N>
+where
+    T: Sub<T, Output = T> + Default,
+{
+    /// Subtract phase by phase with the given policy for missing phases.
+    /// The `-` operator uses `Policy::Union`.
+    pub fn sub_with(self, rhs: Self, policy: Policy) -> Self {
+        self.zip_with(rhs, policy, Sub::sub)
+    }
+}
+
+impl<T, E, const N: usize> PhasesN<Result<T, E>, N> {
+    /// Give the values if every phase in service is Ok, otherwise the first error.
+    pub fn transpose(self) -> Result<PhasesN<T, N>, E> {
+        let mut phases = PhasesN::default();
+        for (phase, value) in phases.0.iter_mut().zip(self.0) {
+            *phase = value.transpose()?;
+        }
+        Ok(phases)
+    }
+}
+
+impl<T, const N: usize> PhasesN<T, N>
 where
     T: Add<T, Output = T>,
 {
     pub fn sum(self) -> Option<T> {
-        add_opt(self.0, add_opt(self.1, self.2))
+        self.0.into_iter().fold(None, add_opt)
     }
 }
 
-impl<T> PhasesOpt<T>
+impl<T, const N: usize> PhasesN<T, N>
 where
     T: Ord,
 {
     pub fn max(self) -> Option<T> {
-        max_opt(self.0, max_opt(self.1, self.2))
+        self.0.into_iter().fold(None, max_opt)
     }
 
     pub fn min(self) -> Option<T> {
-        min_opt(self.0, min_opt(self.1, self.2))
+        self.0.into_iter().fold(None, min_opt)
+    }
+
+    /// The index of the phase with the largest value and that value.
+    /// Ties go to the first phase.
+    pub fn argmax(self) -> Option<(usize, T)> {
+        self.into_iter()
+            .reduce(|best, next| if next.1 > best.1 { next } else { best })
+    }
+
+    /// The index of the phase with the smallest value and that value.
+    /// Ties go to the first phase.
+    pub fn argmin(self) -> Option<(usize, T)> {
+        self.into_iter()
+            .reduce(|best, next| if next.1 < best.1 { next } else { best })
     }
 }
 
-impl<T> Add<PhasesOpt<T>> for PhasesOpt<T>
+impl<T, const N: usize> PhasesN<T, N>
+where
+    T: Ord + Copy + Sub<T, Output = T>,
+{
+    /// The largest value less the smallest among the phases in service.
+    pub fn spread(self) -> Option<T> {
+        Some(self.max()? - self.min()?)
+    }
+}
+
+impl<R, const N: usize> PhasesN<FixedPoint<R>, N>
+where
+    R: Spec,
+{
+    /// The largest deviation of a phase from the average of the phases
+    /// in service, rounded to nearest.
+    pub fn deviation(self) -> Option<FixedPoint<R>> {
+        let (deviation, _, count) = self.deviation_parts()?;
+        Some(FixedPoint(R::from_fixed(saturate(div_round(
+            deviation, count,
+        )))))
+    }
+
+    /// The unbalance as defined by NEMA: the largest deviation from the
+    /// average as a proportion of the magnitude of the average.
+    /// None if no phase is in service or the average is zero.
+    pub fn unbalance(self) -> Option<Ratio> {
+        let (deviation, sum, _) = self.deviation_parts()?;
+        if sum == 0 {
+            return None;
+        }
+        let fixed = div_round(deviation * unit::Ratio::DENOM, sum.abs());
+        Some(Ratio::with_fix4(saturate(fixed)))
+    }
+
+    /// The largest deviation and the sum, both times the number
+    /// of phases in service, and that number.
+    fn deviation_parts(self) -> Option<(Wide, Wide, Wide)> {
+        let count = self.count() as Wide;
+        let sum: Wide = self
+            .iter()
+            .map(|(_, value)| value.0.to_fixed() as Wide)
+            .sum();
+        let deviation = self
+            .iter()
+            .map(|(_, value)| (count * value.0.to_fixed() as Wide - sum).abs())
+            .max()?;
+        Some((deviation, sum, count))
+    }
+}
+
+impl<T, const N: usize> Default for PhasesN<T, N> {
+    fn default() -> Self {
+        Self(array::from_fn(|_| None))
+    }
+}
+
+impl<T, const N: usize> Index<usize> for PhasesN<T, N> {
+    type Output = Option<T>;
+
+    fn index(&self, index: usize) -> &Option<T> {
+        &self.0[index]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for PhasesN<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Option<T> {
+        &mut self.0[index]
+    }
+}
+
+/// Index by phase, where L1 is index 0.
+/// Use `get` for fewer than three phases.
+impl<T> Index<Phase> for PhasesN<T, 3> {
+    type Output = Option<T>;
+
+    fn index(&self, phase: Phase) -> &Option<T> {
+        &self.0[phase as usize]
+    }
+}
+
+impl<T> IndexMut<Phase> for PhasesN<T, 3> {
+    fn index_mut(&mut self, phase: Phase) -> &mut Option<T> {
+        &mut self.0[phase as usize]
+    }
+}
+
+impl<T, const N: usize> From<[Option<T>; N]> for PhasesN<T, N> {
+    fn from(value: [Option<T>; N]) -> Self {
+        Self(value)
+    }
+}
+
+impl<T, const N: usize> From<PhasesN<T, N>> for [Option<T>; N] {
+    fn from(value: PhasesN<T, N>) -> Self {
+        value.0
+    }
+}
+
+/// An iterator over the phases in service of a `PhasesN`
+/// and their values, with phases identified by index.
+pub struct IntoIterN<T, const N: usize>(Enumerate<array::IntoIter<Option<T>, N>>);
+
+impl<T, const N: usize> Iterator for IntoIterN<T, N> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<(usize, T)> {
+        self.0
+            .find_map(|(index, value)| value.map(|value| (index, value)))
+    }
+}
+
+impl<T, const N: usize> IntoIterator for PhasesN<T, N> {
+    type Item = (usize, T);
+    type IntoIter = IntoIterN<T, N>;
+
+    fn into_iter(self) -> IntoIterN<T, N> {
+        IntoIterN(self.0.into_iter().enumerate())
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a PhasesN<T, N> {
+    type Item = (usize, &'a T);
+    type IntoIter = IntoIterN<&'a T, N>;
+
+    fn into_iter(self) -> IntoIterN<&'a T, N> {
+        self.as_ref().into_iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut PhasesN<T, N> {
+    type Item = (usize, &'a mut T);
+    type IntoIter = IntoIterN<&'a mut T, N>;
+
+    fn into_iter(self) -> IntoIterN<&'a mut T, N> {
+        self.as_mut().into_iter()
+    }
+}
+
+impl<T> From<PhasesOpt<T>> for PhasesN<T, 3> {
+    fn from(value: PhasesOpt<T>) -> Self {
+        Self(value.into())
+    }
+}
+
+impl<T> From<PhasesN<T, 3>> for PhasesOpt<T> {
+    fn from(value: PhasesN<T, 3>) -> Self {
+        value.0.into()
+    }
+}
+
+impl<T, const N: usize> Add<PhasesN<T, N>> for PhasesN<T, N>
 where
     T: Add<T, Output = T>,
 {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self {
-        Self(
-            add_opt(self.0, rhs.0),
-            add_opt(self.1, rhs.1),
-            add_opt(self.2, rhs.2),
-        )
+        Self(zip_array(self.0, rhs.0, add_opt))
     }
 }
 
-impl<T> AddAssign<PhasesOpt<T>> for PhasesOpt<T>
+impl<T, const N: usize> AddAssign<PhasesN<T, N>> for PhasesN<T, N>
 where
     T: Add<T, Output = T> + Copy,
 {
-    fn add_assign(&mut self, rhs: PhasesOpt<T>) {
+    fn add_assign(&mut self, rhs: PhasesN<T, N>) {
         *self = *self + rhs;
     }
 }
 
-impl<T> Sub<PhasesOpt<T>> for PhasesOpt<T>
+impl<T, const N: usize> Sub<PhasesN<T, N>> for PhasesN<T, N>
 where
     T: Neg<Output = T> + Sub<T, Output = T>,
 {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self {
-        Self(
-            sub_opt(self.0, rhs.0),
-            sub_opt(self.1, rhs.1),
-            sub_opt(self.2, rhs.2),
-        )
+        Self(zip_array(self.0, rhs.0, sub_opt))
     }
 }
 
-impl<T> SubAssign<PhasesOpt<T>> for PhasesOpt<T>
+impl<T, const N: usize> SubAssign<PhasesN<T, N>> for PhasesN<T, N>
 where
     T: Neg<Output = T> + Sub<T, Output = T> + Copy,
 {
-    fn sub_assign(&mut self, rhs: PhasesOpt<T>) {
+    fn sub_assign(&mut self, rhs: PhasesN<T, N>) {
         *self = *self - rhs;
     }
 }
 
-impl<T> Mul<Float> for PhasesOpt<T>
+/// Multiplication phase by phase, such as voltage by current giving power.
+/// A phase out of service in either operand is out of service in the result.
+impl<T, U, const N: usize> Mul<PhasesN<U, N>> for PhasesN<T, N>
+where
+    T: Mul<U>,
+{
+    type Output = PhasesN<T::Output, N>;
+
+    fn mul(self, rhs: PhasesN<U, N>) -> Self::Output {
+        PhasesN(zip_array(self.0, rhs.0, |a, b| both_opt(a, b, Mul::mul)))
+    }
+}
+
+#[cfg(feature = "float")]
+impl<T, const N: usize> Mul<Float> for PhasesN<T, N>
 where
     T: Mul<Float, Output = T>,
 {
     type Output = Self;
 
     fn mul(self, rhs: Float) -> Self {
-        Self(
-            mul_opt(self.0, rhs),
-            mul_opt(self.1, rhs),
-            mul_opt(self.2, rhs),
-        )
+        Self(self.0.map(|value| mul_opt(value, rhs)))
     }
 }
 
-impl<T> MulAssign<Float> for PhasesOpt<T>
+#[cfg(feature = "float")]
+impl<T, const N: usize> MulAssign<Float> for PhasesN<T, N>
 where
     T: Mul<Float, Output = T> + Copy,
 {
@@ -111,23 +1166,20 @@ where
     }
 }
 
-impl<T> Div<Float> for PhasesOpt<T>
+#[cfg(feature = "float")]
+impl<T, const N: usize> Div<Float> for PhasesN<T, N>
 where
     T: Mul<Float, Output = T>,
 {
     type Output = Self;
 
     fn div(self, rhs: Float) -> Self {
-        let reciprical = 1.0 / rhs;
-        Self(
-            mul_opt(self.0, reciprical),
-            mul_opt(self.1, reciprical),
-            mul_opt(self.2, reciprical),
-        )
+        self * (1.0 / rhs)
     }
 }
 
-impl<T> DivAssign<Float> for PhasesOpt<T>
+#[cfg(feature = "float")]
+impl<T, const N: usize> DivAssign<Float> for PhasesN<T, N>
 where
     T: Mul<Float, Output = T> + Copy,
 {
@@ -136,6 +1188,114 @@ where
     }
 }
 
+impl<T, const N: usize> Mul<Ratio> for PhasesN<T, N>
+where
+    T: Mul<Ratio, Output = T>,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Ratio) -> Self {
+        Self(self.0.map(|value| mul_opt(value, rhs)))
+    }
+}
+
+impl<T, const N: usize> MulAssign<Ratio> for PhasesN<T, N>
+where
+    T: Mul<Ratio, Output = T> + Copy,
+{
+    fn mul_assign(&mut self, rhs: Ratio) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T, const N: usize> Serialize for PhasesN<T, N>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.0.iter())
+    }
+}
+
+impl<'de, T, const N: usize> Deserialize<'de> for PhasesN<T, N>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(PhasesNVisitor(PhantomData))
+    }
+}
+
+struct PhasesNVisitor<T, const N: usize>(PhantomData<T>);
+
+impl<'de, T, const N: usize> Visitor<'de> for PhasesNVisitor<T, N>
+where
+    T: Deserialize<'de>,
+{
+    type Value = PhasesN<T, N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a sequence of {N} phases")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut phases = PhasesN::default();
+        for (index, phase) in phases.0.iter_mut().enumerate() {
+            *phase = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(index, &self))?;
+        }
+        if seq.next_element::<Option<T>>()?.is_some() {
+            return Err(de::Error::invalid_length(N + 1, &self));
+        }
+        Ok(phases)
+    }
+}
+
+fn zip_array<A, B, C, F, const N: usize>(lhs: [A; N], rhs: [B; N], mut f: F) -> [C; N]
+where
+    F: FnMut(A, B) -> C,
+{
+    let mut rhs = rhs.into_iter();
+    lhs.map(|a| match rhs.next() {
+        Some(b) => f(a, b),
+        None => unreachable!(),
+    })
+}
+
+fn zip_opt<T, U, V, F>(lhs: Option<T>, rhs: Option<U>, policy: Policy, f: F) -> Option<V>
+where
+    T: Default,
+    U: Default,
+    F: FnOnce(T, U) -> V,
+{
+    match (lhs, rhs, policy) {
+        (Some(a), Some(b), _) => Some(f(a, b)),
+        (Some(a), None, Policy::Union) => Some(f(a, U::default())),
+        (None, Some(b), Policy::Union) => Some(f(T::default(), b)),
+        _ => None,
+    }
+}
+
+fn both_opt<T, U, V, F>(lhs: Option<T>, rhs: Option<U>, f: F) -> Option<V>
+where
+    F: FnOnce(T, U) -> V,
+{
+    match (lhs, rhs) {
+        (Some(a), Some(b)) => Some(f(a, b)),
+        _ => None,
+    }
+}
+
 fn add_opt<T>(lhs: Option<T>, rhs: Option<T>) -> Option<T>
 where
     T: Add<T, Output = T>,
@@ -160,9 +1320,9 @@ where
     }
 }
 
-fn mul_opt<T>(lhs: Option<T>, rhs: Float) -> Option<T>
+fn mul_opt<T, S>(lhs: Option<T>, rhs: S) -> Option<T>
 where
-    T: Mul<Float, Output = T>,
+    T: Mul<S, Output = T>,
 {
     lhs.map(|lhs| lhs * rhs)
 }
@@ -190,3 +1350,324 @@ where
         (None, None) => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexing() {
+        let mut phases = PhasesOpt(Some(1), None, Some(3));
+        assert_eq!(phases[Phase::L1], Some(1));
+        assert_eq!(phases[Phase::L2], None);
+        phases[Phase::L2] = Some(2);
+        assert_eq!(phases, PhasesOpt(Some(1), Some(2), Some(3)));
+        assert_eq!(
+            <[Option<i32>; 3]>::from(phases),
+            [Some(1), Some(2), Some(3)]
+        );
+        assert_eq!(
+            PhasesOpt::from([None, Some(2), None]),
+            PhasesOpt(None, Some(2), None)
+        );
+    }
+
+    #[test]
+    fn iteration() {
+        let mut phases = PhasesOpt(Some(1), None, Some(3));
+        {
+            let mut iter = phases.iter();
+            assert_eq!(iter.next(), Some((Phase::L1, &1)));
+            assert_eq!(iter.next(), Some((Phase::L3, &3)));
+            assert_eq!(iter.next(), None);
+        }
+        for (_, value) in phases.iter_mut() {
+            *value *= 10;
+        }
+        let doubled: PhasesOpt<i32> = phases
+            .into_iter()
+            .map(|(phase, value)| (phase, value * 2))
+            .collect();
+        assert_eq!(doubled, PhasesOpt(Some(20), None, Some(60)));
+        let shifted: PhasesOpt<i32> = [(Phase::L3, 1), (Phase::L2, 2), (Phase::L3, 3)]
+            .into_iter()
+            .collect();
+        assert_eq!(shifted, PhasesOpt(None, Some(2), Some(3)));
+    }
+
+    #[test]
+    fn combinators() {
+        let phases = PhasesOpt(Some(1), None, Some(3));
+        assert_eq!(
+            phases.map(|value| value * 2),
+            PhasesOpt(Some(2), None, Some(6))
+        );
+        assert_eq!(
+            phases.and_then(|value| (value > 1).then_some(value)),
+            PhasesOpt(None, None, Some(3))
+        );
+        assert_eq!(
+            phases.filter(|value| *value < 3),
+            PhasesOpt(Some(1), None, None)
+        );
+        assert_eq!(phases.as_ref(), PhasesOpt(Some(&1), None, Some(&3)));
+    }
+
+    #[test]
+    fn zipping() {
+        let a = PhasesOpt(Some(10), Some(20), None);
+        let b = PhasesOpt(Some(1), None, Some(3));
+        assert_eq!(
+            a.zip_with(b, Policy::Union, |a, b| a - b),
+            PhasesOpt(Some(9), Some(20), Some(-3))
+        );
+        assert_eq!(
+            a.zip_with(b, Policy::Intersection, |a, b| a - b),
+            PhasesOpt(Some(9), None, None)
+        );
+        assert_eq!(a.zip_with(b, Policy::Union, |a, b| a - b), a - b);
+    }
+
+    #[test]
+    fn missing_phases() {
+        type Current = FixedPoint<unit::Amp>;
+        let (a, b) = (Some(Current::with_fix1(100)), Some(Current::with_fix1(30)));
+        let measured = PhasesOpt(a, a, None);
+        let limit = PhasesOpt(b, None, b);
+
+        // union: a missing phase counts as zero, as for summing contributions
+        let union = PhasesOpt(
+            Some(Current::with_fix1(70)),
+            a,
+            Some(Current::with_fix1(-30)),
+        );
+        assert_eq!(measured - limit, union);
+        assert_eq!(measured.sub_with(limit, Policy::Union), union);
+        assert_eq!(
+            measured + limit,
+            PhasesOpt(Some(Current::with_fix1(130)), a, b)
+        );
+
+        // intersection: a missing phase stays missing, as for comparing measurements
+        let intersection = PhasesOpt(Some(Current::with_fix1(70)), None, None);
+        assert_eq!(measured.sub_with(limit, Policy::Intersection), intersection);
+        assert_eq!(measured.strict() - limit.strict(), Strict(intersection));
+        let mut total = measured.strict();
+        total += limit.strict();
+        assert_eq!(
+            PhasesOpt::from(total),
+            PhasesOpt(Some(Current::with_fix1(130)), None, None)
+        );
+        assert_eq!(measured.add_with(limit, Policy::Intersection), total.0);
+    }
+
+    #[test]
+    fn imbalance() {
+        type Current = FixedPoint<unit::Amp>;
+        let current = PhasesOpt(
+            Some(Current::with_fix1(320)),
+            Some(Current::with_fix1(100)),
+            Some(Current::with_fix1(100)),
+        );
+        assert_eq!(current.argmax(), Some((Phase::L1, Current::with_fix1(320))));
+        assert_eq!(current.argmin(), Some((Phase::L2, Current::with_fix1(100))));
+        assert_eq!(current.spread(), Some(Current::with_fix1(220)));
+        // the average is 17.3 A
+        assert_eq!(current.deviation(), Some(Current::with_fix1(147)));
+        assert_eq!(current.unbalance(), Some(Ratio::with_fix4(8462)));
+
+        let single = PhasesOpt(None, Some(Current::with_fix1(160)), None);
+        assert_eq!(single.spread(), Some(Current::ZERO));
+        assert_eq!(single.deviation(), Some(Current::ZERO));
+        assert_eq!(single.unbalance(), Some(Ratio::ZERO));
+        assert_eq!(single.argmax(), Some((Phase::L2, Current::with_fix1(160))));
+
+        let idle = PhasesOpt(Some(Current::ZERO), Some(Current::ZERO), None);
+        assert_eq!(idle.unbalance(), None);
+        assert_eq!(PhasesOpt::<Current>::default().deviation(), None);
+        assert_eq!(PhasesOpt::<Current>::default().argmin(), None);
+    }
+
+    #[test]
+    fn all_phases() {
+        type Voltage = FixedPoint<unit::Volt>;
+        let nominal = Phases::splat(Voltage::with_fix1(2300));
+        let measured = Phases(
+            Voltage::with_fix1(2310),
+            Voltage::with_fix1(2280),
+            Voltage::with_fix1(2350),
+        );
+        let deviation = measured - nominal;
+        assert_eq!(deviation[Phase::L2], Voltage::with_fix1(-20));
+        assert_eq!(deviation.max(), Voltage::with_fix1(50));
+        assert_eq!(deviation.min(), Voltage::with_fix1(-20));
+        assert_eq!(deviation.sum(), Voltage::with_fix1(40));
+        assert_eq!(
+            nominal * Ratio::percent(110),
+            Phases::splat(Voltage::with_fix1(2530))
+        );
+        let text = serde_json::to_string(&measured).unwrap();
+        assert_eq!(text, "[2310,2280,2350]");
+        assert_eq!(
+            serde_json::from_str::<Phases<Voltage>>(&text).unwrap(),
+            measured
+        );
+    }
+
+    #[test]
+    fn optional_conversion() {
+        let all = Phases(1, 2, 3);
+        assert_eq!(PhasesOpt::from(all), PhasesOpt(Some(1), Some(2), Some(3)));
+        assert_eq!(Phases::try_from(PhasesOpt::from(all)), Ok(all));
+        let some = PhasesOpt(Some(1), None, Some(3));
+        assert_eq!(Phases::try_from(some), Err(OutOfService(Phase::L2)));
+        assert_eq!(some.unwrap_or_default(), Phases(1, 0, 3));
+        assert_eq!(some.unwrap_or(7), Phases(1, 7, 3));
+    }
+
+    #[test]
+    fn n_phases() {
+        type Current = FixedPoint<unit::Amp>;
+        let a = Some(Current::with_fix1(100));
+        let b = Some(Current::with_fix1(60));
+        let split: SplitPhase<Current> = PhasesN([a, b]);
+        assert_eq!(split.count(), 2);
+        assert_eq!(split.sum(), Some(Current::with_fix1(160)));
+        assert_eq!(split.max(), a);
+        assert_eq!(split.min(), b);
+        assert_eq!(
+            split - PhasesN([b, None]),
+            PhasesN([Some(Current::with_fix1(40)), b])
+        );
+        assert_eq!(
+            split.zip_with(PhasesN([b, None]), Policy::Intersection, |a, b| a - b),
+            PhasesN([Some(Current::with_fix1(40)), None])
+        );
+        assert_eq!(split.iter().last(), Some((1, &Current::with_fix1(60))));
+
+        let single: SinglePhase<Current> = PhasesN::default();
+        assert_eq!(single.sum(), None);
+        assert_eq!(
+            (single + PhasesN([a])) * Ratio::percent(50),
+            PhasesN([Some(Current::with_fix1(50))])
+        );
+    }
+
+    #[test]
+    fn n_phases_shared() {
+        type Voltage = FixedPoint<unit::Volt>;
+        type Current = FixedPoint<unit::Amp>;
+        type Power = FixedPoint<unit::Watt>;
+
+        // written once for any number of phases
+        fn headroom<const N: usize>(
+            limit: PhasesN<Current, N>,
+            measured: PhasesN<Current, N>,
+        ) -> Option<Current> {
+            limit.sub_with(measured, Policy::Intersection).min()
+        }
+        let (a, b) = (Some(Current::with_fix1(320)), Some(Current::with_fix1(100)));
+        assert_eq!(
+            headroom(PhasesN([a]), PhasesN([b])),
+            Some(Current::with_fix1(220))
+        );
+        assert_eq!(
+            headroom(PhasesN([a, a]), PhasesN([b, a])),
+            Some(Current::ZERO)
+        );
+        assert_eq!(
+            headroom(PhasesOpt(a, a, None).into(), PhasesOpt(b, None, b).into()),
+            Some(Current::with_fix1(220))
+        );
+
+        let mut split: SplitPhase<Current> = PhasesN([a, b]);
+        assert_eq!(split.get(Phase::L2), Some(&b));
+        assert_eq!(split.get(Phase::L3), None);
+        assert_eq!(split.argmax(), Some((0, Current::with_fix1(320))));
+        assert_eq!(split.argmin(), Some((1, Current::with_fix1(100))));
+        assert_eq!(split.spread(), Some(Current::with_fix1(220)));
+        assert_eq!(split.deviation(), Some(Current::with_fix1(110)));
+        assert_eq!(split.unbalance(), Some(Ratio::with_fix4(5238)));
+        for (_, value) in split.iter_mut() {
+            *value *= Ratio::percent(50);
+        }
+        *split.get_mut(Phase::L2).unwrap() = None;
+        assert!(split.get_mut(Phase::L3).is_none());
+        assert_eq!(split, PhasesN([Some(Current::with_fix1(160)), None]));
+        assert_eq!(
+            split.strict() + PhasesN([b, b]).strict(),
+            Strict(PhasesN([Some(Current::with_fix1(260)), None]))
+        );
+        assert_eq!(
+            split.add_with(PhasesN([b, b]), Policy::Union),
+            PhasesN([Some(Current::with_fix1(260)), b])
+        );
+
+        let voltage: SplitPhase<Voltage> = PhasesN::from_fn(|_| Some(Voltage::with_fix1(1200)));
+        assert_eq!((voltage * split).sum(), Some(Power::with_fix0(1920)));
+        let parsed: SinglePhase<Result<i32, &str>> = PhasesN([Some(Err("L1"))]);
+        assert_eq!(parsed.transpose(), Err("L1"));
+    }
+
+    #[test]
+    fn n_phases_interchange() {
+        let three = PhasesOpt(Some(1), None, Some(3));
+        let text = serde_json::to_string(&three).unwrap();
+        assert_eq!(text, "[1,null,3]");
+        let n: PhasesN<i32, 3> = serde_json::from_str(&text).unwrap();
+        assert_eq!(n, PhasesN::from(three));
+        assert_eq!(serde_json::to_string(&n).unwrap(), text);
+        assert_eq!(PhasesOpt::from(n), three);
+        assert!(serde_json::from_str::<SplitPhase<i32>>(&text).is_err());
+    }
+
+    #[test]
+    fn power() {
+        type Voltage = FixedPoint<unit::Volt>;
+        type Current = FixedPoint<unit::Amp>;
+        type Power = FixedPoint<unit::Watt>;
+        let voltage = PhasesOpt(
+            Some(Voltage::with_fix1(2300)),
+            Some(Voltage::with_fix1(2400)),
+            None,
+        );
+        let current = PhasesOpt(
+            Some(Current::with_fix1(100)),
+            None,
+            Some(Current::with_fix1(50)),
+        );
+        let power = voltage * current;
+        assert_eq!(power, PhasesOpt(Some(Power::with_fix0(2300)), None, None));
+        let current = PhasesOpt(
+            Some(Current::with_fix1(100)),
+            Some(Current::with_fix1(160)),
+            None,
+        );
+        let power_factor = PhasesOpt(Some(Ratio::percent(90)), Some(Ratio::ONE), None);
+        let active = voltage * current * power_factor;
+        assert_eq!(
+            active,
+            PhasesOpt(
+                Some(Power::with_fix0(2070)),
+                Some(Power::with_fix0(3840)),
+                None
+            )
+        );
+        assert_eq!(active.sum(), Some(Power::with_fix0(5910)));
+    }
+
+    #[test]
+    fn transpose() {
+        let parsed: PhasesOpt<Result<i32, &str>> = PhasesOpt(Some(Ok(1)), None, Some(Ok(3)));
+        assert_eq!(parsed.transpose(), Ok(PhasesOpt(Some(1), None, Some(3))));
+        let parsed: PhasesOpt<Result<i32, &str>> = PhasesOpt(Some(Ok(1)), Some(Err("L2")), None);
+        assert_eq!(parsed.transpose(), Err("L2"));
+    }
+
+    #[test]
+    fn from_fn() {
+        let phases = PhasesOpt::from_fn(|phase| (phase != Phase::L2).then_some(phase));
+        assert_eq!(phases, PhasesOpt(Some(Phase::L1), None, Some(Phase::L3)));
+        assert_eq!(phases.count(), 2);
+    }
+}
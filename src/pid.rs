@@ -0,0 +1,349 @@
+use core::{fmt, marker::PhantomData};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    div_round,
+    ratio::{PreciseRatio, Ratio},
+    saturate, unit, Fixed, FixedPoint, Spec, Wide,
+};
+
+/// The configuration of a `Pid` controller with output `FixedPoint<S>`.
+///
+/// The gains are in physical units with six decimal places so that
+/// small integral gains are not lost.  With error `R` and output `S`,
+/// `kp` is in units of S per R, `ki` is in S per R per `period`
+/// and `kd` is in S per R per `period` of rate of change.
+/// The period is measured in the same units as the elapsed times given
+/// to `Pid::update`, for example 1000 for gains per second with
+/// elapsed times in milliseconds.
+///
+/// The derivative term is smoothed by an exponential moving average
+/// with factor `smoothing`, where one means no smoothing.
+///
+/// The output limits must satisfy `min <= max`, which is checked
+/// when a configuration is deserialized.  Reversed limits are
+/// otherwise treated as if they were given in order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[serde(bound = "", try_from = "RawPidConfig<S>")]
+pub struct PidConfig<S>
+where
+    S: Spec,
+{
+    pub kp: PreciseRatio,
+    pub ki: PreciseRatio,
+    pub kd: PreciseRatio,
+    pub period: u32,
+    pub min: FixedPoint<S>,
+    pub max: FixedPoint<S>,
+    pub smoothing: Ratio,
+}
+
+/// The reason a PID configuration is invalid.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PidError {
+    /// The minimum output is greater than the maximum.
+    LimitsReversed,
+}
+
+impl fmt::Display for PidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PidError::LimitsReversed => {
+                write!(f, "a controller minimum output must not exceed its maximum")
+            }
+        }
+    }
+}
+
+impl<S> PidConfig<S>
+where
+    S: Spec,
+{
+    /// A proportional-integral controller.
+    pub const fn pi(
+        kp: PreciseRatio,
+        ki: PreciseRatio,
+        period: u32,
+        min: FixedPoint<S>,
+        max: FixedPoint<S>,
+    ) -> Self {
+        Self {
+            kp,
+            ki,
+            kd: PreciseRatio::ZERO,
+            period,
+            min,
+            max,
+            smoothing: Ratio::ONE,
+        }
+    }
+
+    /// The same controller with a derivative term.
+    pub const fn with_derivative(self, kd: PreciseRatio, smoothing: Ratio) -> Self {
+        Self {
+            kd,
+            smoothing,
+            ..self
+        }
+    }
+
+    /// Check that the output limits are in order.
+    pub fn validate(&self) -> Result<(), PidError> {
+        if self.min.0.to_fixed() > self.max.0.to_fixed() {
+            return Err(PidError::LimitsReversed);
+        }
+        Ok(())
+    }
+}
+
+/// The unvalidated serialized form of a PidConfig.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct RawPidConfig<S>
+where
+    S: Spec,
+{
+    kp: PreciseRatio,
+    ki: PreciseRatio,
+    kd: PreciseRatio,
+    period: u32,
+    min: FixedPoint<S>,
+    max: FixedPoint<S>,
+    smoothing: Ratio,
+}
+
+impl<S> TryFrom<RawPidConfig<S>> for PidConfig<S>
+where
+    S: Spec,
+{
+    type Error = PidError;
+
+    fn try_from(value: RawPidConfig<S>) -> Result<Self, PidError> {
+        let config = Self::pi(value.kp, value.ki, value.period, value.min, value.max)
+            .with_derivative(value.kd, value.smoothing);
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// A PID controller with error `FixedPoint<R>` and output `FixedPoint<S>`.
+///
+/// Integer arithmetic is used throughout so that results are
+/// bit-exact on every target.  The integral is clamped to the
+/// output limits so that it does not wind up while the output
+/// is saturated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Pid<R, S>
+where
+    R: Spec,
+    S: Spec,
+{
+    config: PidConfig<S>,
+    /// The integral term in output fixed units times PreciseRatio::DENOM, R::DENOM and the period.
+    integral: i128,
+    /// The smoothed derivative term in output fixed units times PreciseRatio::DENOM.
+    derivative: i128,
+    previous: Option<Fixed>,
+    marker: PhantomData<R>,
+}
+
+impl<R, S> Pid<R, S>
+where
+    R: Spec,
+    S: Spec,
+{
+    pub const fn new(config: PidConfig<S>) -> Self {
+        Self {
+            config,
+            integral: 0,
+            derivative: 0,
+            previous: None,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn config(&self) -> &PidConfig<S> {
+        &self.config
+    }
+
+    /// Discard the integral and derivative history.
+    pub fn reset(&mut self) {
+        self.integral = 0;
+        self.derivative = 0;
+        self.previous = None;
+    }
+
+    /// Accept the next error, given the time elapsed since the previous error,
+    /// and give the new output.
+    pub fn update(&mut self, error: FixedPoint<R>, elapsed: u32) -> FixedPoint<S> {
+        let config = &self.config;
+        let error = error.0.to_fixed();
+        let ratio = unit::PreciseRatio::DENOM as i128;
+        let (min, max) = (
+            config.min.0.to_fixed() as i128,
+            config.max.0.to_fixed() as i128,
+        );
+        let (min, max) = (min.min(max), min.max(max));
+
+        // output fixed units per error fixed unit, times PreciseRatio::DENOM
+        let gain = |k: PreciseRatio| k.fix6() as i128 * S::DENOM as i128;
+        let period = config.period.max(1) as i128;
+
+        let proportional = div_round(gain(config.kp) * error as i128, ratio * R::DENOM as i128);
+
+        let integral_denom = ratio * R::DENOM as i128 * period;
+        self.integral += gain(config.ki) * error as i128 * elapsed as i128;
+        self.integral = self
+            .integral
+            .clamp(min * integral_denom, max * integral_denom);
+        let integral = div_round(self.integral, integral_denom);
+
+        if let Some(previous) = self.previous {
+            if elapsed > 0 {
+                let change = error as i128 - previous as i128;
+                let raw = div_round(
+                    gain(config.kd) * change * period,
+                    R::DENOM as i128 * elapsed as i128,
+                );
+                let smoothing = config.smoothing.fix4() as i128;
+                self.derivative += div_round(
+                    smoothing * (raw - self.derivative),
+                    unit::Ratio::DENOM as i128,
+                );
+            }
+        }
+        self.previous = Some(error);
+        let derivative = div_round(self.derivative, ratio);
+
+        let output = (proportional + integral + derivative).clamp(min, max);
+        FixedPoint(S::from_fixed(saturate(output as Wide)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Current = FixedPoint<unit::Amp>;
+    type Power = FixedPoint<unit::Watt>;
+
+    fn run(pid: &mut Pid<unit::Watt, unit::Amp>, errors: &[Fixed], elapsed: u32) -> Vec<Fixed> {
+        errors
+            .iter()
+            .map(|error| pid.update(Power::with_fix0(*error), elapsed).fix1())
+            .collect()
+    }
+
+    const LIMITS: (Current, Current) = (Current::with_fix1(-320), Current::with_fix1(320));
+
+    #[test]
+    fn proportional() {
+        // 0.01 A per W
+        let config = PidConfig::pi(
+            PreciseRatio::with_fix6(10_000),
+            PreciseRatio::ZERO,
+            1000,
+            LIMITS.0,
+            LIMITS.1,
+        );
+        let mut pid = Pid::new(config);
+        assert_eq!(
+            run(&mut pid, &[1000, -250, 5, 100_000], 100),
+            [100, -25, 1, 320]
+        );
+    }
+
+    #[test]
+    fn integral() {
+        // 0.01 A per W per second with 500 ms steps
+        let config = PidConfig::pi(
+            PreciseRatio::ZERO,
+            PreciseRatio::with_fix6(10_000),
+            1000,
+            LIMITS.0,
+            Current::with_fix1(10),
+        );
+        let mut pid = Pid::new(config);
+        assert_eq!(run(&mut pid, &[100, 100, 100, 100], 500), [5, 10, 10, 10]);
+        // no wind up while saturated
+        assert_eq!(run(&mut pid, &[-100, -100, 0, 0], 500), [5, 0, 0, 0]);
+        pid.update(Power::with_fix0(100), 500);
+        pid.reset();
+        assert_eq!(pid.update(Power::ZERO, 500), Current::ZERO);
+    }
+
+    #[test]
+    fn small_integral() {
+        // 0.00005 A per W per step, below the precision of a Ratio
+        let config = PidConfig::pi(
+            PreciseRatio::ZERO,
+            PreciseRatio::with_fix6(50),
+            1,
+            LIMITS.0,
+            LIMITS.1,
+        );
+        let mut pid = Pid::new(config);
+        assert_eq!(run(&mut pid, &[1000, 1000, 1000, 1000], 1), [1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn derivative() {
+        // 0.1 A per W/s, unsmoothed then smoothed by half
+        let config = PidConfig::pi(
+            PreciseRatio::ZERO,
+            PreciseRatio::ZERO,
+            1000,
+            LIMITS.0,
+            LIMITS.1,
+        );
+        let kd = PreciseRatio::with_fix6(100_000);
+        let mut pid = Pid::new(config.with_derivative(kd, Ratio::ONE));
+        assert_eq!(run(&mut pid, &[0, 10, 30, 30], 1000), [0, 10, 20, 0]);
+        let mut pid = Pid::new(config.with_derivative(kd, Ratio::percent(50)));
+        assert_eq!(run(&mut pid, &[0, 10, 30, 30], 1000), [0, 5, 13, 6]);
+    }
+
+    #[test]
+    fn serialization() {
+        let config = PidConfig::pi(
+            PreciseRatio::with_fix6(10_000),
+            PreciseRatio::with_fix6(500),
+            1000,
+            LIMITS.0,
+            LIMITS.1,
+        );
+        let text = serde_json::to_string(&config).unwrap();
+        assert_eq!(
+            text,
+            r#"{"kp":10000,"ki":500,"kd":0,"period":1000,"min":-320,"max":320,"smoothing":10000}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<PidConfig<unit::Amp>>(&text).unwrap(),
+            config
+        );
+        let text =
+            r#"{"kp":10000,"ki":500,"kd":0,"period":1000,"min":320,"max":-320,"smoothing":10000}"#;
+        assert!(serde_json::from_str::<PidConfig<unit::Amp>>(text).is_err());
+    }
+
+    #[test]
+    fn reversed_limits() {
+        let config = PidConfig::pi(
+            PreciseRatio::with_fix6(10_000),
+            PreciseRatio::ZERO,
+            1000,
+            LIMITS.1,
+            LIMITS.0,
+        );
+        assert_eq!(config.validate(), Err(PidError::LimitsReversed));
+        let mut pid = Pid::new(config);
+        assert_eq!(
+            run(&mut pid, &[1000, 100_000, -100_000], 100),
+            [100, 320, -320]
+        );
+    }
+}
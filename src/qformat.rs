@@ -0,0 +1,212 @@
+use core::ops::{Mul, MulAssign};
+
+use crate::{div_saturate, saturate, shift_round, unit, Fixed, FixedPoint, Spec, Wide};
+
+/// A binary scaled representation with `BITS` fraction bits,
+/// such that `DENOM == 1 << BITS`.
+///
+/// Multiplication, division and conversion among these formats
+/// use shifts in place of float or decimal arithmetic.
+/// Products are rounded to nearest with ties upward, quotients
+/// to nearest with ties away from zero, and all results saturate.
+pub trait QFormat: Spec {
+    const BITS: u32;
+}
+
+/// The fraction bits of `R`, checking at compile time that they agree with its DENOM.
+const fn bits<R>() -> u32
+where
+    R: QFormat,
+{
+    const {
+        assert!(
+            R::DENOM == 1 << R::BITS,
+            "QFormat BITS must agree with DENOM"
+        )
+    };
+    R::BITS
+}
+
+impl<R> FixedPoint<R>
+where
+    R: QFormat,
+{
+    /// Divide by a value of the same format.
+    /// Division by zero saturates according to the sign of self.
+    pub fn quotient(self, rhs: Self) -> Self {
+        bits::<R>();
+        let fixed = div_saturate(self.0.to_fixed(), rhs.0.to_fixed(), R::DENOM);
+        Self(R::from_fixed(saturate(fixed)))
+    }
+
+    /// Conversion to another binary format by shifting.
+    pub fn requantize<S>(self) -> FixedPoint<S>
+    where
+        S: QFormat,
+    {
+        let (from, to) = (bits::<R>(), bits::<S>());
+        let fixed = self.0.to_fixed() as Wide;
+        let fixed = if to >= from {
+            fixed << (to - from)
+        } else {
+            shift_round(fixed, from - to)
+        };
+        FixedPoint(S::from_fixed(saturate(fixed)))
+    }
+
+    /// Treat self as a fraction of a full scale value in any representation
+    /// and give the corresponding value.  For example, a Q15 ADC reading
+    /// times the full scale current of the sensor.
+    pub fn scale<S>(self, full_scale: FixedPoint<S>) -> FixedPoint<S>
+    where
+        S: Spec,
+    {
+        let fixed = self.0.to_fixed() as Wide * full_scale.0.to_fixed() as Wide;
+        FixedPoint(S::from_fixed(saturate(shift_round(fixed, bits::<R>()))))
+    }
+
+    /// The fraction that a value represents of a full scale value.
+    /// This is the inverse of `scale`.
+    pub fn fraction<S>(value: FixedPoint<S>, full_scale: FixedPoint<S>) -> Self
+    where
+        S: Spec,
+    {
+        bits::<R>();
+        let fixed = div_saturate(value.0.to_fixed(), full_scale.0.to_fixed(), R::DENOM);
+        Self(R::from_fixed(saturate(fixed)))
+    }
+}
+
+impl<R> Mul<FixedPoint<R>> for FixedPoint<R>
+where
+    R: QFormat,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let fixed = self.0.to_fixed() as Wide * rhs.0.to_fixed() as Wide;
+        Self(R::from_fixed(saturate(shift_round(fixed, bits::<R>()))))
+    }
+}
+
+impl<R> MulAssign<FixedPoint<R>> for FixedPoint<R>
+where
+    R: QFormat,
+{
+    fn mul_assign(&mut self, rhs: FixedPoint<R>) {
+        *self = *self * rhs;
+    }
+}
+
+// constants and const constructors for the binary representations defined in unit
+impl FixedPoint<unit::Q15> {
+    pub const ZERO: Self = Self::with_q15(0);
+    pub const MAX: Self = Self::with_q15(i16::MAX);
+    pub const MIN: Self = Self::with_q15(i16::MIN);
+
+    /// Construct from an integer interpreted at 2^15 scale.
+    pub const fn with_q15(value: i16) -> Self {
+        Self(unit::Q15(value))
+    }
+
+    /// Extract an integer at 2^15 scale.
+    pub const fn q15(self) -> i16 {
+        self.0 .0
+    }
+}
+
+impl FixedPoint<unit::Q16_16> {
+    pub const ZERO: Self = Self::with_q16(0);
+    pub const ONE: Self = Self::with_q16(1 << 16);
+
+    /// Construct from an integer interpreted at 2^16 scale.
+    pub const fn with_q16(value: Fixed) -> Self {
+        Self(unit::Q16_16(value))
+    }
+
+    /// Extract an integer at 2^16 scale.
+    pub const fn q16(self) -> Fixed {
+        self.0 .0
+    }
+}
+
+impl FixedPoint<unit::Q31> {
+    pub const ZERO: Self = Self::with_q31(0);
+    pub const MAX: Self = Self::with_q31(Fixed::MAX);
+    pub const MIN: Self = Self::with_q31(Fixed::MIN);
+
+    /// Construct from an integer interpreted at 2^31 scale.
+    pub const fn with_q31(value: Fixed) -> Self {
+        Self(unit::Q31(value))
+    }
+
+    /// Extract an integer at 2^31 scale.
+    pub const fn q31(self) -> Fixed {
+        self.0 .0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ratio::Ratio;
+
+    type Fraction = FixedPoint<unit::Q15>;
+    type Gain = FixedPoint<unit::Q16_16>;
+    type Precise = FixedPoint<unit::Q31>;
+    type Current = FixedPoint<unit::Amp>;
+
+    #[test]
+    fn multiplication() {
+        let half = Fraction::with_q15(1 << 14);
+        assert_eq!(half * half, Fraction::with_q15(1 << 13));
+        assert_eq!(Fraction::MIN * Fraction::MIN, Fraction::MAX);
+        assert_eq!(
+            Gain::ONE * Gain::with_q16(-3 << 15),
+            Gain::with_q16(-3 << 15)
+        );
+    }
+
+    #[test]
+    fn division() {
+        let half = Fraction::with_q15(1 << 14);
+        let quarter = Fraction::with_q15(1 << 13);
+        assert_eq!(quarter.quotient(half), half);
+        assert_eq!(half.quotient(quarter), Fraction::MAX);
+        assert_eq!(half.quotient(Fraction::ZERO), Fraction::MAX);
+        assert_eq!(Gain::ONE.quotient(Gain::with_q16(3 << 16)).q16(), 21845);
+    }
+
+    #[test]
+    fn requantization() {
+        let half = Fraction::with_q15(1 << 14);
+        assert_eq!(half.requantize::<unit::Q31>(), Precise::with_q31(1 << 30));
+        assert_eq!(half.requantize::<unit::Q16_16>(), Gain::with_q16(1 << 15));
+        assert_eq!(
+            Precise::with_q31(3 << 15).requantize::<unit::Q15>().q15(),
+            2
+        );
+    }
+
+    #[test]
+    fn decimal_conversion() {
+        let full_scale = Current::with_fix1(500);
+        let reading = Fraction::with_q15(9830);
+        assert_eq!(reading.scale(full_scale), Current::with_fix1(150));
+        assert_eq!(
+            Fraction::fraction(Current::with_fix1(150), full_scale).q15(),
+            9830
+        );
+        assert_eq!(
+            Gain::with_q16(3 << 15).rescale::<unit::Ratio>(),
+            Ratio::with_fix4(15000)
+        );
+        assert_eq!(Ratio::with_fix4(2500).rescale::<unit::Q15>().q15(), 8192);
+        assert_eq!(
+            FixedPoint::<unit::Percent>::with_fix1(-500)
+                .rescale::<unit::Q15>()
+                .q15(),
+            -16384
+        );
+    }
+}
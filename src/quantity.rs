@@ -0,0 +1,29 @@
+//! Marker types for the physical quantity that a `Spec` represents.
+//!
+//! Representations of the same quantity can be rescaled into one another
+//! exactly.  The base unit of each quantity is given below and
+//! `Spec::PREFIX` relates a representation to it.
+
+/// Electric potential in volts.
+pub enum Voltage {}
+
+/// Electric current in amps.
+pub enum Current {}
+
+/// Power in watts.
+pub enum Power {}
+
+/// Energy in watt hours.
+pub enum Energy {}
+
+/// Temperature in degrees Celsius.
+pub enum Temperature {}
+
+/// Angle in degrees.
+pub enum Angle {}
+
+/// Resistance in ohms.
+pub enum Resistance {}
+
+/// A dimensionless number such as a ratio, percentage or binary fraction.
+pub enum Dimensionless {}
@@ -0,0 +1,356 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{phases::PhasesOpt, ratio::Ratio, Fixed, FixedPoint, Spec, Wide};
+
+/// The slew rate limits of a `Ramp`: the output may rise by at most
+/// `rise` and fall by at most `fall` in each `period` of elapsed time.
+///
+/// The period is measured in the same units as the elapsed times given
+/// to `Ramp::next`, for example 1000 for a rate per second with
+/// elapsed times in milliseconds.  A period of zero removes the limits.
+///
+/// The rates must not be negative, which is checked by `new` and
+/// when rates are deserialized.  Negative rates are otherwise
+/// treated as zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[serde(bound = "", try_from = "RawRampRate<R>")]
+pub struct RampRate<R>
+where
+    R: Spec,
+{
+    pub rise: FixedPoint<R>,
+    pub fall: FixedPoint<R>,
+    pub period: u32,
+}
+
+/// The reason ramp rates are invalid.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RampError {
+    /// The rise or fall rate is negative.
+    NegativeRate,
+}
+
+impl fmt::Display for RampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RampError::NegativeRate => write!(f, "a ramp rate must not be negative"),
+        }
+    }
+}
+
+impl<R> RampRate<R>
+where
+    R: Spec,
+{
+    pub fn new(rise: FixedPoint<R>, fall: FixedPoint<R>, period: u32) -> Result<Self, RampError> {
+        let rate = Self { rise, fall, period };
+        rate.validate()?;
+        Ok(rate)
+    }
+
+    /// Rates given as proportions of a rated value per period,
+    /// rounded to the precision of `R`.
+    pub fn of_rated(
+        rated: FixedPoint<R>,
+        rise: Ratio,
+        fall: Ratio,
+        period: u32,
+    ) -> Result<Self, RampError> {
+        Self::new(rated * rise, rated * fall, period)
+    }
+
+    /// Check that the rates are not negative.
+    pub fn validate(&self) -> Result<(), RampError> {
+        if self.rise.0.to_fixed() < 0 || self.fall.0.to_fixed() < 0 {
+            return Err(RampError::NegativeRate);
+        }
+        Ok(())
+    }
+}
+
+/// The unvalidated serialized form of a RampRate.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct RawRampRate<R>
+where
+    R: Spec,
+{
+    rise: FixedPoint<R>,
+    fall: FixedPoint<R>,
+    period: u32,
+}
+
+impl<R> TryFrom<RawRampRate<R>> for RampRate<R>
+where
+    R: Spec,
+{
+    type Error = RampError;
+
+    fn try_from(value: RawRampRate<R>) -> Result<Self, RampError> {
+        Self::new(value.rise, value.fall, value.period)
+    }
+}
+
+/// A slew rate limiter, which moves its output towards a target no
+/// faster than a `RampRate`.
+///
+/// Integer arithmetic is used throughout.  Movement too small to
+/// change the output in one step is carried to the next so that
+/// the average rate is exact for any elapsed times.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ramp<R>
+where
+    R: Spec,
+{
+    rate: RampRate<R>,
+    output: FixedPoint<R>,
+    /// Carried movement in fixed units times elapsed time,
+    /// positive while rising and negative while falling.
+    remainder: Wide,
+}
+
+impl<R> Ramp<R>
+where
+    R: Spec,
+{
+    /// A ramp with the given initial output.
+    pub const fn new(rate: RampRate<R>, initial: FixedPoint<R>) -> Self {
+        Self {
+            rate,
+            output: initial,
+            remainder: 0,
+        }
+    }
+
+    pub fn rate(&self) -> &RampRate<R> {
+        &self.rate
+    }
+
+    pub fn output(&self) -> FixedPoint<R> {
+        self.output
+    }
+
+    /// Set the output immediately, such as when the load is switched off.
+    pub fn reset(&mut self, output: FixedPoint<R>) {
+        self.output = output;
+        self.remainder = 0;
+    }
+
+    /// Move towards the target given the time elapsed since the previous step
+    /// and give the new output.
+    pub fn next(&mut self, target: FixedPoint<R>, elapsed: u32) -> FixedPoint<R> {
+        let output = self.output.0.to_fixed() as Wide;
+        let distance = target.0.to_fixed() as Wide - output;
+        let period = self.rate.period as Wide;
+        if distance == 0 || period == 0 {
+            self.reset(target);
+            return target;
+        }
+
+        let (rate, carried) = if distance > 0 {
+            (self.rate.rise, self.remainder.max(0))
+        } else {
+            (self.rate.fall, (-self.remainder).max(0))
+        };
+        let budget = (rate.0.to_fixed() as Wide).max(0) * elapsed as Wide + carried;
+        let step = budget / period;
+        if step >= distance.abs() {
+            self.reset(target);
+            return target;
+        }
+
+        let sign = distance.signum();
+        self.output = FixedPoint(R::from_fixed((output + sign * step) as Fixed));
+        self.remainder = sign * (budget % period);
+        self.output
+    }
+}
+
+/// A ramp for each phase of a `PhasesOpt` setpoint with a common rate.
+/// Each phase starts from zero when it comes into service.  When a phase
+/// is out of service its output is None.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PhasesRamp<R>
+where
+    R: Spec,
+{
+    rate: RampRate<R>,
+    ramps: PhasesOpt<Ramp<R>>,
+}
+
+impl<R> PhasesRamp<R>
+where
+    R: Spec,
+{
+    /// Ramps with no phases in service.
+    pub const fn new(rate: RampRate<R>) -> Self {
+        Self {
+            rate,
+            ramps: PhasesOpt(None, None, None),
+        }
+    }
+
+    pub fn rate(&self) -> &RampRate<R> {
+        &self.rate
+    }
+
+    /// The output of each phase in service.
+    pub fn output(&self) -> PhasesOpt<FixedPoint<R>> {
+        self.ramps.map(|ramp| ramp.output())
+    }
+
+    pub fn next(
+        &mut self,
+        target: PhasesOpt<FixedPoint<R>>,
+        elapsed: u32,
+    ) -> PhasesOpt<FixedPoint<R>> {
+        let rate = self.rate;
+        PhasesOpt(
+            next_opt(&mut self.ramps.0, rate, target.0, elapsed),
+            next_opt(&mut self.ramps.1, rate, target.1, elapsed),
+            next_opt(&mut self.ramps.2, rate, target.2, elapsed),
+        )
+    }
+}
+
+fn next_opt<R>(
+    ramp: &mut Option<Ramp<R>>,
+    rate: RampRate<R>,
+    target: Option<FixedPoint<R>>,
+    elapsed: u32,
+) -> Option<FixedPoint<R>>
+where
+    R: Spec,
+{
+    match target {
+        Some(target) => {
+            let ramp = ramp.get_or_insert_with(|| Ramp::new(rate, FixedPoint(R::from_fixed(0))));
+            Some(ramp.next(target, elapsed))
+        }
+        None => {
+            *ramp = None;
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unit;
+
+    type Current = FixedPoint<unit::Amp>;
+    type Power = FixedPoint<unit::Watt>;
+
+    fn run<const N: usize>(
+        ramp: &mut Ramp<unit::Amp>,
+        target: Current,
+        elapsed: u32,
+    ) -> [Fixed; N] {
+        core::array::from_fn(|_| ramp.next(target, elapsed).fix1())
+    }
+
+    #[test]
+    fn rise_and_fall() {
+        // 1.5 A/s up and 2 A/s down with 100 ms steps
+        let rate = RampRate::new(Current::with_fix1(15), Current::with_fix1(20), 1000).unwrap();
+        let mut ramp = Ramp::new(rate, Current::ZERO);
+        assert_eq!(
+            run(&mut ramp, Current::with_fix1(320), 100),
+            [1, 3, 4, 6, 7]
+        );
+        assert_eq!(run(&mut ramp, Current::with_fix1(0), 100), [5, 3, 1, 0, 0]);
+        assert_eq!(run(&mut ramp, Current::with_fix1(10), 1000), [10; 5]);
+    }
+
+    #[test]
+    fn direction_change() {
+        let rate = RampRate::new(Current::with_fix1(5), Current::with_fix1(5), 1000).unwrap();
+        let mut ramp = Ramp::new(rate, Current::with_fix1(100));
+        assert_eq!(ramp.next(Current::with_fix1(200), 100).fix1(), 100);
+        // the carried rise does not hasten the fall
+        assert_eq!(ramp.next(Current::ZERO, 100).fix1(), 100);
+        assert_eq!(ramp.next(Current::ZERO, 100).fix1(), 99);
+        assert_eq!(ramp.next(Current::ZERO, 2000).fix1(), 89);
+    }
+
+    #[test]
+    fn unlimited() {
+        let rate = RampRate::new(Current::ZERO, Current::ZERO, 0).unwrap();
+        let mut ramp = Ramp::new(rate, Current::ZERO);
+        assert_eq!(
+            ramp.next(Current::with_fix1(320), 1),
+            Current::with_fix1(320)
+        );
+    }
+
+    #[test]
+    fn rated() {
+        // 10% of 7.4 kW per second up and 50% down
+        let rated = Power::with_fix0(7400);
+        let rate = RampRate::of_rated(rated, Ratio::percent(10), Ratio::percent(50), 1000).unwrap();
+        assert_eq!(rate.rise, Power::with_fix0(740));
+        assert_eq!(rate.fall, Power::with_fix0(3700));
+        let mut ramp = Ramp::new(rate, Power::ZERO);
+        assert_eq!(ramp.next(rated, 2500), Power::with_fix0(1850));
+    }
+
+    #[test]
+    fn negative() {
+        let (rate, negative) = (Current::with_fix1(10), Current::with_fix1(-10));
+        assert_eq!(
+            RampRate::new(negative, rate, 1000),
+            Err(RampError::NegativeRate)
+        );
+        assert_eq!(
+            RampRate::new(rate, negative, 1000),
+            Err(RampError::NegativeRate)
+        );
+        assert!(RampRate::of_rated(Current::with_fix1(-160), Ratio::ONE, Ratio::ONE, 1).is_err());
+    }
+
+    #[test]
+    fn serialization() {
+        let rate = RampRate::new(Current::with_fix1(15), Current::with_fix1(20), 1000).unwrap();
+        let text = serde_json::to_string(&rate).unwrap();
+        assert_eq!(text, r#"{"rise":15,"fall":20,"period":1000}"#);
+        assert_eq!(
+            serde_json::from_str::<RampRate<unit::Amp>>(&text).unwrap(),
+            rate
+        );
+        assert!(serde_json::from_str::<RampRate<unit::Amp>>(
+            r#"{"rise":-15,"fall":20,"period":1000}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn phases() {
+        let rate = RampRate::new(Current::with_fix1(10), Current::with_fix1(10), 1000).unwrap();
+        let mut ramp = PhasesRamp::new(rate);
+        assert_eq!(ramp.output(), PhasesOpt(None, None, None));
+        let target = Some(Current::with_fix1(160));
+        assert_eq!(
+            ramp.next(PhasesOpt(target, target, None), 1000),
+            PhasesOpt(
+                Some(Current::with_fix1(10)),
+                Some(Current::with_fix1(10)),
+                None
+            )
+        );
+        assert_eq!(
+            ramp.next(PhasesOpt(target, None, target), 1000),
+            PhasesOpt(
+                Some(Current::with_fix1(20)),
+                None,
+                Some(Current::with_fix1(10))
+            )
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use core::ops::{Div, Mul, MulAssign};
+
+use crate::{div_round, div_saturate, saturate, unit, Fixed, FixedPoint, Spec, Wide};
+
+/// A dimensionless gain, calibration factor or proportion
+/// with four decimal places.
+///
+/// Scaling a `FixedPoint` by a `Ratio` uses integer arithmetic
+/// only and so gives bit-exact results on every target.
+/// Results are rounded to nearest with ties away from zero
+/// and saturate.
+///
+/// The quotient of two values of the same representation is a Ratio.
+/// Division by zero saturates according to the sign of the dividend.
+pub type Ratio = FixedPoint<unit::Ratio>;
+
+impl Ratio {
+    pub const ZERO: Self = Self::with_fix4(0);
+    pub const ONE: Self = Self::with_fix4(10000);
+
+    /// Construct from a integer interpreted at 10000x scale.
+    pub const fn with_fix4(value: Fixed) -> Self {
+        Self(unit::Ratio(value))
+    }
+
+    /// Extract an integer at 10000x scale
+    pub const fn fix4(self) -> Fixed {
+        self.0 .0
+    }
+
+    /// Construct from a whole number of percent.
+    pub const fn percent(value: Fixed) -> Self {
+        Self::with_fix4(value.saturating_mul(100))
+    }
+
+    /// Construct from a whole number of parts per thousand.
+    pub const fn per_mille(value: Fixed) -> Self {
+        Self::with_fix4(value.saturating_mul(10))
+    }
+}
+
+/// A dimensionless gain or factor with six decimal places,
+/// in the range of about +/-2147, for gains too small for a `Ratio`.
+pub type PreciseRatio = FixedPoint<unit::PreciseRatio>;
+
+impl PreciseRatio {
+    pub const ZERO: Self = Self::with_fix6(0);
+    pub const ONE: Self = Self::with_fix6(1_000_000);
+
+    /// Construct from a integer interpreted at 1000000x scale.
+    pub const fn with_fix6(value: Fixed) -> Self {
+        Self(unit::PreciseRatio(value))
+    }
+
+    /// Extract an integer at 1000000x scale
+    pub const fn fix6(self) -> Fixed {
+        self.0 .0
+    }
+}
+
+impl From<FixedPoint<unit::Percent>> for Ratio {
+    fn from(value: FixedPoint<unit::Percent>) -> Self {
+        let fixed = value.0.to_fixed() as Wide * unit::Ratio::DENOM;
+        Self::with_fix4(saturate(div_round(fixed, 100 * unit::Percent::DENOM)))
+    }
+}
+
+impl<R> Mul<Ratio> for FixedPoint<R>
+where
+    R: Spec,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Ratio) -> Self {
+        let fixed = self.0.to_fixed() as Wide * rhs.fix4() as Wide;
+        Self(R::from_fixed(saturate(div_round(
+            fixed,
+            unit::Ratio::DENOM,
+        ))))
+    }
+}
+
+impl<R> MulAssign<Ratio> for FixedPoint<R>
+where
+    R: Spec,
+{
+    fn mul_assign(&mut self, rhs: Ratio) {
+        *self = *self * rhs;
+    }
+}
+
+impl<R> Div<FixedPoint<R>> for FixedPoint<R>
+where
+    R: Spec,
+{
+    type Output = Ratio;
+
+    fn div(self, rhs: Self) -> Ratio {
+        let fixed = div_saturate(self.0.to_fixed(), rhs.0.to_fixed(), unit::Ratio::DENOM);
+        Ratio::with_fix4(saturate(fixed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::phases::PhasesOpt;
+
+    type Current = FixedPoint<unit::Amp>;
+    type Power = FixedPoint<unit::Watt>;
+
+    #[test]
+    fn scaling() {
+        assert_eq!(
+            Current::with_fix1(320) * Ratio::percent(80),
+            Current::with_fix1(256)
+        );
+        assert_eq!(
+            Current::with_fix1(15) * Ratio::with_fix4(5000),
+            Current::with_fix1(8)
+        );
+        assert_eq!(
+            Current::with_fix1(-15) * Ratio::with_fix4(5000),
+            Current::with_fix1(-8)
+        );
+        assert_eq!(
+            Current::with_fix1(100) * Ratio::per_mille(400),
+            Current::with_fix1(40)
+        );
+    }
+
+    #[test]
+    fn quotient() {
+        assert_eq!(
+            Power::with_fix0(7400) / Power::with_fix0(22000),
+            Ratio::with_fix4(3364)
+        );
+        assert_eq!(
+            Power::with_fix0(-1) / Power::with_fix0(3),
+            Ratio::with_fix4(-3333)
+        );
+        assert_eq!(Power::with_fix0(0) / Power::with_fix0(0), Ratio::ZERO);
+        assert_eq!(
+            Power::with_fix0(1) / Power::with_fix0(0),
+            Ratio::with_fix4(Fixed::MAX)
+        );
+    }
+
+    #[test]
+    fn percentages() {
+        let soc = FixedPoint::<unit::Percent>::with_fix1(804);
+        assert_eq!(Ratio::from(soc), Ratio::with_fix4(8040));
+        assert_eq!(Ratio::percent(25).to_string(), "0.25");
+    }
+
+    #[test]
+    fn phases() {
+        let currents = PhasesOpt(
+            Some(Current::with_fix1(100)),
+            None,
+            Some(Current::with_fix1(60)),
+        );
+        assert_eq!(
+            currents * Ratio::percent(50),
+            PhasesOpt(
+                Some(Current::with_fix1(50)),
+                None,
+                Some(Current::with_fix1(30))
+            )
+        );
+    }
+}
@@ -0,0 +1,378 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{div_round, phases::PhasesOpt, saturate, sqrt_round, Fixed, FixedPoint, Spec, Wide};
+
+/// Running statistics of a series of `FixedPoint` values,
+/// such as for a reporting window.
+///
+/// Statistics are accumulated without allocation or floats
+/// and can be persisted with serde.  Accumulators for separate
+/// periods or sources can be merged.
+///
+/// Up to `u32::MAX` values are accumulated exactly.  Values beyond
+/// that are not accumulated.  Statistics are checked for consistency
+/// when deserialized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[serde(bound = "", try_from = "RawStats<R>")]
+pub struct Stats<R>
+where
+    R: Spec,
+{
+    count: u32,
+    sum: Wide,
+    sum_squares: u128,
+    min: Option<FixedPoint<R>>,
+    max: Option<FixedPoint<R>>,
+}
+
+/// The reason deserialized statistics are invalid.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum StatsError {
+    /// The sums, minimum and maximum are not possible for the count.
+    Inconsistent,
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatsError::Inconsistent => write!(f, "statistics are inconsistent with their count"),
+        }
+    }
+}
+
+impl<R> Stats<R>
+where
+    R: Spec,
+{
+    /// An accumulator with no values.
+    pub const fn new() -> Self {
+        Self {
+            count: 0,
+            sum: 0,
+            sum_squares: 0,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Accumulate a value, unless `u32::MAX` values have been accumulated.
+    pub fn push(&mut self, value: FixedPoint<R>) {
+        let Some(count) = self.count.checked_add(1) else {
+            return;
+        };
+        let fixed = value.0.to_fixed() as Wide;
+        self.count = count;
+        self.sum += fixed;
+        self.sum_squares += (fixed * fixed) as u128;
+        self.min = Some(lesser(self.min, value));
+        self.max = Some(greater(self.max, value));
+    }
+
+    /// Accumulate the values accumulated in another, unless the
+    /// total would exceed `u32::MAX` values.
+    pub fn merge(&mut self, other: &Self) {
+        let Some(count) = self.count.checked_add(other.count) else {
+            return;
+        };
+        self.count = count;
+        self.sum += other.sum;
+        self.sum_squares += other.sum_squares;
+        if let Some(value) = other.min {
+            self.min = Some(lesser(self.min, value));
+        }
+        if let Some(value) = other.max {
+            self.max = Some(greater(self.max, value));
+        }
+    }
+
+    /// Discard all values.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// The number of values.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn min(&self) -> Option<FixedPoint<R>> {
+        self.min
+    }
+
+    pub fn max(&self) -> Option<FixedPoint<R>> {
+        self.max
+    }
+
+    /// The mean value, rounded to nearest.
+    pub fn mean(&self) -> Option<FixedPoint<R>> {
+        if self.count == 0 {
+            return None;
+        }
+        let mean = div_round(self.sum, self.count as Wide);
+        Some(FixedPoint(R::from_fixed(saturate(mean))))
+    }
+
+    /// The population standard deviation, rounded to nearest.
+    pub fn std_dev(&self) -> Option<FixedPoint<R>> {
+        if self.count == 0 {
+            return None;
+        }
+        // the bounds on the sums for the count keep these within u128
+        let count = self.count as u128;
+        let spread = (count * self.sum_squares)
+            .saturating_sub(self.sum.unsigned_abs() as u128 * self.sum.unsigned_abs() as u128);
+        let variance = (spread + count * count / 2) / (count * count);
+        let root = sqrt_round(variance as u64);
+        Some(FixedPoint(R::from_fixed(saturate(root as Wide))))
+    }
+}
+
+impl<R> Default for Stats<R>
+where
+    R: Spec,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The unvalidated serialized form of Stats.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct RawStats<R>
+where
+    R: Spec,
+{
+    count: u32,
+    sum: Wide,
+    sum_squares: u128,
+    min: Option<FixedPoint<R>>,
+    max: Option<FixedPoint<R>>,
+}
+
+impl<R> TryFrom<RawStats<R>> for Stats<R>
+where
+    R: Spec,
+{
+    type Error = StatsError;
+
+    fn try_from(value: RawStats<R>) -> Result<Self, StatsError> {
+        let count = value.count as i128;
+        let consistent = match (value.min, value.max) {
+            (None, None) => value.count == 0 && value.sum == 0 && value.sum_squares == 0,
+            (Some(min), Some(max)) => {
+                let (min, max) = (min.0.to_fixed() as i128, max.0.to_fixed() as i128);
+                let bound = Fixed::MIN as i128 * Fixed::MIN as i128;
+                count > 0
+                    && min <= max
+                    && (min * count..=max * count).contains(&(value.sum as i128))
+                    && value.sum_squares <= (bound * count) as u128
+            }
+            _ => false,
+        };
+        if !consistent {
+            return Err(StatsError::Inconsistent);
+        }
+        Ok(Self {
+            count: value.count,
+            sum: value.sum,
+            sum_squares: value.sum_squares,
+            min: value.min,
+            max: value.max,
+        })
+    }
+}
+
+/// Running statistics for each phase of a `PhasesOpt` series.
+/// Values for phases that are out of service are not accumulated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[serde(bound = "")]
+pub struct PhasesStats<R>(pub Stats<R>, pub Stats<R>, pub Stats<R>)
+where
+    R: Spec;
+
+impl<R> PhasesStats<R>
+where
+    R: Spec,
+{
+    pub const fn new() -> Self {
+        Self(Stats::new(), Stats::new(), Stats::new())
+    }
+
+    /// Accumulate the values of the phases in service.
+    pub fn push(&mut self, value: PhasesOpt<FixedPoint<R>>) {
+        if let Some(value) = value.0 {
+            self.0.push(value);
+        }
+        if let Some(value) = value.1 {
+            self.1.push(value);
+        }
+        if let Some(value) = value.2 {
+            self.2.push(value);
+        }
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.0.merge(&other.0);
+        self.1.merge(&other.1);
+        self.2.merge(&other.2);
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// A statistic of each phase.  A phase is None if it has no values.
+    fn each<F>(&self, f: F) -> PhasesOpt<FixedPoint<R>>
+    where
+        F: Fn(&Stats<R>) -> Option<FixedPoint<R>>,
+    {
+        PhasesOpt(f(&self.0), f(&self.1), f(&self.2))
+    }
+
+    pub fn min(&self) -> PhasesOpt<FixedPoint<R>> {
+        self.each(Stats::min)
+    }
+
+    pub fn max(&self) -> PhasesOpt<FixedPoint<R>> {
+        self.each(Stats::max)
+    }
+
+    pub fn mean(&self) -> PhasesOpt<FixedPoint<R>> {
+        self.each(Stats::mean)
+    }
+
+    pub fn std_dev(&self) -> PhasesOpt<FixedPoint<R>> {
+        self.each(Stats::std_dev)
+    }
+}
+
+impl<R> Default for PhasesStats<R>
+where
+    R: Spec,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn lesser<R>(current: Option<FixedPoint<R>>, value: FixedPoint<R>) -> FixedPoint<R>
+where
+    R: Spec,
+{
+    match current {
+        Some(current) if current.0.to_fixed() <= value.0.to_fixed() => current,
+        _ => value,
+    }
+}
+
+fn greater<R>(current: Option<FixedPoint<R>>, value: FixedPoint<R>) -> FixedPoint<R>
+where
+    R: Spec,
+{
+    match current {
+        Some(current) if current.0.to_fixed() >= value.0.to_fixed() => current,
+        _ => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unit;
+
+    type Current = FixedPoint<unit::Amp>;
+
+    fn stats(values: &[i32]) -> Stats<unit::Amp> {
+        let mut stats = Stats::new();
+        for value in values {
+            stats.push(Current::with_fix1(*value));
+        }
+        stats
+    }
+
+    #[test]
+    fn accumulation() {
+        let stats = stats(&[20, 40, 40, 40, 50, 50, 70, 90]);
+        assert_eq!(stats.count(), 8);
+        assert_eq!(stats.min(), Some(Current::with_fix1(20)));
+        assert_eq!(stats.max(), Some(Current::with_fix1(90)));
+        assert_eq!(stats.mean(), Some(Current::with_fix1(50)));
+        assert_eq!(stats.std_dev(), Some(Current::with_fix1(20)));
+        assert_eq!(Stats::<unit::Amp>::new().mean(), None);
+    }
+
+    #[test]
+    fn merging() {
+        let mut stats1 = stats(&[20, 40, 40, 40]);
+        stats1.merge(&stats(&[50, 50, 70, 90]));
+        assert_eq!(stats1, stats(&[20, 40, 40, 40, 50, 50, 70, 90]));
+        stats1.reset();
+        assert_eq!(stats1, Stats::new());
+    }
+
+    #[test]
+    fn serialization() {
+        let stats = stats(&[-5, 5]);
+        let text = serde_json::to_string(&stats).unwrap();
+        assert_eq!(
+            text,
+            r#"{"count":2,"sum":0,"sum_squares":50,"min":-5,"max":5}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Stats<unit::Amp>>(&text).unwrap(),
+            stats
+        );
+        for text in [
+            r#"{"count":0,"sum":5,"sum_squares":0,"min":null,"max":null}"#,
+            r#"{"count":2,"sum":0,"sum_squares":50,"min":5,"max":-5}"#,
+            r#"{"count":2,"sum":20,"sum_squares":50,"min":-5,"max":5}"#,
+            r#"{"count":1,"sum":0,"sum_squares":0,"min":0,"max":null}"#,
+        ] {
+            assert!(serde_json::from_str::<Stats<unit::Amp>>(text).is_err());
+        }
+    }
+
+    #[test]
+    fn large_values() {
+        let mut stats = Stats::<unit::Amp>::new();
+        for value in [-Fixed::MAX, Fixed::MAX, -Fixed::MAX, Fixed::MAX] {
+            stats.push(Current::with_fix1(value));
+        }
+        assert_eq!(stats.mean(), Some(Current::ZERO));
+        assert_eq!(stats.std_dev(), Some(Current::with_fix1(Fixed::MAX)));
+
+        let mut full = stats;
+        full.count = u32::MAX;
+        let before = full;
+        full.push(Current::ZERO);
+        full.merge(&stats);
+        assert_eq!(full, before);
+    }
+
+    #[test]
+    fn phases() {
+        let mut stats = PhasesStats::new();
+        stats.push(PhasesOpt(
+            Some(Current::with_fix1(10)),
+            None,
+            Some(Current::with_fix1(30)),
+        ));
+        stats.push(PhasesOpt(Some(Current::with_fix1(20)), None, None));
+        assert_eq!(
+            stats.mean(),
+            PhasesOpt(
+                Some(Current::with_fix1(15)),
+                None,
+                Some(Current::with_fix1(30))
+            )
+        );
+        assert_eq!(stats.1.count(), 0);
+        assert_eq!(stats.2.count(), 1);
+    }
+}
@@ -0,0 +1,710 @@
+//! Conversion of resistive temperature sensor readings to Celsius.
+//!
+//! NTC thermistors are modelled by the `Beta` or `SteinhartHart`
+//! equations and platinum RTDs by the `CallendarVanDusen` equation.
+//! These use floats (feature `float`) and can generate a `Curve` from
+//! resistance to temperature, which is then evaluated with integer
+//! arithmetic only.  Curves for PT100 and PT1000 sensors and common
+//! 10 kohm NTC thermistors are provided as constants for targets
+//! without floating point.
+#[cfg(feature = "float")]
+use core::{
+    f32::consts::{FRAC_1_SQRT_2, LN_2, SQRT_2},
+    fmt,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{curve::Curve, div_round, ratio::Ratio, saturate, unit, FixedPoint, Spec, Wide};
+#[cfg(feature = "float")]
+use crate::{curve::CurveError, Fixed, Float};
+
+/// The position of a sensor in a voltage divider with a reference resistor.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Divider {
+    /// The sensor is between the measured node and ground.
+    LowSide,
+    /// The sensor is between the supply and the measured node.
+    HighSide,
+}
+
+/// The sensor resistance given the divider ratio, which is the measured
+/// voltage over the supply voltage, and the reference resistance.
+/// Gives None if the ratio is not strictly between 0 and 1,
+/// which indicates an open or shorted sensor.
+pub fn divider_resistance(
+    ratio: Ratio,
+    reference: FixedPoint<unit::Ohm>,
+    divider: Divider,
+) -> Option<FixedPoint<unit::Ohm>> {
+    let ratio = ratio.fix4() as Wide;
+    let whole = unit::Ratio::DENOM;
+    if ratio <= 0 || ratio >= whole {
+        return None;
+    }
+    let (numer, denom) = match divider {
+        Divider::LowSide => (ratio, whole - ratio),
+        Divider::HighSide => (whole - ratio, ratio),
+    };
+    let ohms = div_round(reference.0.to_fixed() as Wide * numer, denom);
+    Some(FixedPoint(unit::Ohm(saturate(ohms))))
+}
+
+const fn point(ohms: i32, celsius: i32) -> (FixedPoint<unit::Ohm>, FixedPoint<unit::Celsius>) {
+    (
+        FixedPoint::<unit::Ohm>::with_fix2(ohms),
+        FixedPoint::<unit::Celsius>::with_fix2(celsius),
+    )
+}
+
+/// Resistance to temperature for an IEC 60751 PT100 sensor
+/// from -50 C to 250 C in 25 C steps.
+/// Interpolation error is below 0.05 C over this range.
+pub const PT100_CURVE: Curve<unit::Ohm, unit::Celsius, 13> = Curve::new([
+    point(8031, -5000),
+    point(9019, -2500),
+    point(10000, 0),
+    point(10973, 2500),
+    point(11940, 5000),
+    point(12899, 7500),
+    point(13851, 10000),
+    point(14795, 12500),
+    point(15733, 15000),
+    point(16663, 17500),
+    point(17586, 20000),
+    point(18501, 22500),
+    point(19410, 25000),
+]);
+
+/// Resistance to temperature for an IEC 60751 PT1000 sensor
+/// from -50 C to 250 C in 25 C steps.
+/// Interpolation error is below 0.05 C over this range.
+pub const PT1000_CURVE: Curve<unit::Ohm, unit::Celsius, 13> = Curve::new([
+    point(80306, -5000),
+    point(90192, -2500),
+    point(100000, 0),
+    point(109735, 2500),
+    point(119397, 5000),
+    point(128987, 7500),
+    point(138506, 10000),
+    point(147951, 12500),
+    point(157325, 15000),
+    point(166627, 17500),
+    point(175856, 20000),
+    point(185013, 22500),
+    point(194098, 25000),
+]);
+
+/// Resistance to temperature for a 10 kohm NTC thermistor with
+/// Beta 3950 K (25 C to 50 C) from -40 C to 125 C in 5 C steps.
+/// Interpolation error is below 0.25 C over this range.
+pub const NTC_10K_B3950_CURVE: Curve<unit::Ohm, unit::Celsius, 34> = Curve::new([
+    point(35883, 12500),
+    point(40709, 12000),
+    point(46334, 11500),
+    point(52914, 11000),
+    point(60642, 10500),
+    point(69752, 10000),
+    point(80537, 9500),
+    point(93358, 9000),
+    point(108667, 8500),
+    point(127032, 8000),
+    point(149168, 7500),
+    point(175984, 7000),
+    point(208637, 6500),
+    point(248616, 6000),
+    point(297844, 5500),
+    point(358818, 5000),
+    point(434814, 4500),
+    point(530147, 4000),
+    point(650553, 3500),
+    point(803714, 3000),
+    point(1000000, 2500),
+    point(1253533, 2000),
+    point(1583715, 1500),
+    point(2017458, 1000),
+    point(2592456, 500),
+    point(3362060, 0),
+    point(4402605, -500),
+    point(5824571, -1000),
+    point(7789811, -1500),
+    point(10538469, -2000),
+    point(14431694, -2500),
+    point(20020390, -3000),
+    point(28157683, -3500),
+    point(40185972, -4000),
+]);
+
+/// Resistance to temperature for a 10 kohm NTC thermistor with
+/// Beta 3435 K (25 C to 85 C) from -40 C to 125 C in 5 C steps.
+/// Interpolation error is below 0.25 C over this range.
+pub const NTC_10K_B3435_CURVE: Curve<unit::Ohm, unit::Celsius, 34> = Curve::new([
+    point(55374, 12500),
+    point(61796, 12000),
+    point(69157, 11500),
+    point(77623, 11000),
+    point(87392, 10500),
+    point(98704, 10000),
+    point(111848, 9500),
+    point(127181, 9000),
+    point(145135, 8500),
+    point(166244, 8000),
+    point(191167, 7500),
+    point(220723, 7000),
+    point(255935, 6500),
+    point(298085, 6000),
+    point(348794, 5500),
+    point(410119, 5000),
+    point(484687, 4500),
+    point(575876, 4000),
+    point(688061, 3500),
+    point(826941, 3000),
+    point(1000000, 2500),
+    point(1217141, 2000),
+    point(1491568, 1500),
+    point(1841044, 1000),
+    point(2289664, 500),
+    point(2870429, 0),
+    point(3628967, -500),
+    point(4629020, -1000),
+    point(5960598, -1500),
+    point(7752255, -2000),
+    point(10189803, -2500),
+    point(13545245, -3000),
+    point(18222117, -3500),
+    point(24827652, -4000),
+]);
+
+/// The Celsius temperature of absolute zero, negated.
+#[cfg(feature = "float")]
+const KELVIN: Float = 273.15;
+
+/// The characteristic of a resistive temperature sensor.
+#[cfg(feature = "float")]
+pub trait Sensor {
+    /// The resistance in ohms at a temperature in degrees Celsius.
+    fn ohms(&self, celsius: Float) -> Float;
+
+    /// The temperature in degrees Celsius at a resistance in ohms,
+    /// which must be positive.
+    fn celsius(&self, ohms: Float) -> Float;
+
+    /// The temperature at a resistance, rounded to nearest,
+    /// or None if the resistance is not positive or the model
+    /// gives no finite temperature, as when its parameters are invalid.
+    fn temperature(&self, resistance: FixedPoint<unit::Ohm>) -> Option<FixedPoint<unit::Celsius>> {
+        if resistance.0.to_fixed() <= 0 {
+            return None;
+        }
+        let celsius = self.celsius(resistance.to_float());
+        if !celsius.is_finite() {
+            return None;
+        }
+        Some(round(celsius))
+    }
+
+    /// The resistance at a temperature, rounded to nearest.
+    fn resistance(&self, temperature: FixedPoint<unit::Celsius>) -> FixedPoint<unit::Ohm> {
+        round(self.ohms(temperature.to_float()))
+    }
+
+    /// A curve from resistance to temperature with `N` points at equal
+    /// temperature steps from `lo` to `hi`, for conversion
+    /// without floating point.
+    fn curve<const N: usize>(
+        &self,
+        lo: FixedPoint<unit::Celsius>,
+        hi: FixedPoint<unit::Celsius>,
+    ) -> Result<Curve<unit::Ohm, unit::Celsius, N>, CurveError> {
+        let (lo, hi) = (lo.to_float(), hi.to_float());
+        let step = if N > 1 {
+            (hi - lo) / (N - 1) as Float
+        } else {
+            0.0
+        };
+        let mut points = core::array::from_fn(|i| {
+            let temperature = round(lo + step * i as Float);
+            (self.resistance(temperature), temperature)
+        });
+        points.sort_unstable_by_key(|point| point.0);
+        Curve::try_new(points)
+    }
+}
+
+/// The Beta model of an NTC thermistor with resistance `r0`
+/// at temperature `t0`, typically 25 C.
+///
+/// `r0` and `beta` must be positive, which is checked by `new`
+/// and when a model is deserialized.  A model with invalid
+/// parameters gives no temperature.
+#[cfg(feature = "float")]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[serde(try_from = "RawBeta")]
+pub struct Beta {
+    pub r0: FixedPoint<unit::Ohm>,
+    pub t0: FixedPoint<unit::Celsius>,
+    /// The Beta constant in kelvin.
+    pub beta: Float,
+}
+
+/// The reason a Beta model is invalid.
+#[cfg(feature = "float")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BetaError {
+    /// The resistance at `t0` is not positive.
+    ResistanceNotPositive,
+    /// The Beta constant is not positive and finite.
+    BetaNotPositive,
+}
+
+#[cfg(feature = "float")]
+impl fmt::Display for BetaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BetaError::ResistanceNotPositive => {
+                write!(f, "a thermistor resistance must be positive")
+            }
+            BetaError::BetaNotPositive => {
+                write!(f, "a thermistor Beta constant must be positive and finite")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "float")]
+impl Beta {
+    /// A model with valid parameters.
+    pub fn new(
+        r0: FixedPoint<unit::Ohm>,
+        t0: FixedPoint<unit::Celsius>,
+        beta: Float,
+    ) -> Result<Self, BetaError> {
+        let model = Self { r0, t0, beta };
+        model.validate()?;
+        Ok(model)
+    }
+
+    /// Check that `r0` and `beta` are positive.
+    pub fn validate(&self) -> Result<(), BetaError> {
+        if self.r0.0.to_fixed() <= 0 {
+            return Err(BetaError::ResistanceNotPositive);
+        }
+        if !(self.beta.is_finite() && self.beta > 0.0) {
+            return Err(BetaError::BetaNotPositive);
+        }
+        Ok(())
+    }
+}
+
+/// The unvalidated serialized form of a Beta model.
+#[cfg(feature = "float")]
+#[derive(Deserialize)]
+struct RawBeta {
+    r0: FixedPoint<unit::Ohm>,
+    t0: FixedPoint<unit::Celsius>,
+    beta: Float,
+}
+
+#[cfg(feature = "float")]
+impl TryFrom<RawBeta> for Beta {
+    type Error = BetaError;
+
+    fn try_from(value: RawBeta) -> Result<Self, BetaError> {
+        Self::new(value.r0, value.t0, value.beta)
+    }
+}
+
+#[cfg(feature = "float")]
+impl Sensor for Beta {
+    fn ohms(&self, celsius: Float) -> Float {
+        let t0 = self.t0.to_float() + KELVIN;
+        self.r0.to_float() * exp(self.beta * (1.0 / (celsius + KELVIN) - 1.0 / t0))
+    }
+
+    fn celsius(&self, ohms: Float) -> Float {
+        if self.validate().is_err() {
+            return Float::NAN;
+        }
+        let t0 = self.t0.to_float() + KELVIN;
+        1.0 / (1.0 / t0 + ln(ohms / self.r0.to_float()) / self.beta) - KELVIN
+    }
+}
+
+/// The Steinhart-Hart model of an NTC thermistor,
+/// `1/T = a + b ln(R) + c ln(R)^3` with T in kelvin and R in ohms.
+#[cfg(feature = "float")]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SteinhartHart {
+    pub a: Float,
+    pub b: Float,
+    pub c: Float,
+}
+
+#[cfg(feature = "float")]
+impl Sensor for SteinhartHart {
+    fn ohms(&self, celsius: Float) -> Float {
+        // solve the cubic in ln(R) by Newton's method
+        let target = 1.0 / (celsius + KELVIN) - self.a;
+        let mut y = target / self.b;
+        for _ in 0..NEWTON_STEPS {
+            let residual = self.b * y + self.c * y * y * y - target;
+            y -= residual / (self.b + 3.0 * self.c * y * y);
+        }
+        exp(y)
+    }
+
+    fn celsius(&self, ohms: Float) -> Float {
+        let y = ln(ohms);
+        1.0 / (self.a + self.b * y + self.c * y * y * y) - KELVIN
+    }
+}
+
+/// The Callendar-Van Dusen model of a platinum RTD,
+/// `R = r0 (1 + a t + b t^2 + c (t - 100) t^3)` with t in Celsius
+/// where the c term applies below 0 C only.
+#[cfg(feature = "float")]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CallendarVanDusen {
+    pub r0: FixedPoint<unit::Ohm>,
+    pub a: Float,
+    pub b: Float,
+    pub c: Float,
+}
+
+#[cfg(feature = "float")]
+impl CallendarVanDusen {
+    /// An IEC 60751 PT100 sensor.
+    pub const PT100: Self = Self::iec60751(FixedPoint::<unit::Ohm>::with_fix2(10000));
+
+    /// An IEC 60751 PT1000 sensor.
+    pub const PT1000: Self = Self::iec60751(FixedPoint::<unit::Ohm>::with_fix2(100000));
+
+    /// A sensor with the IEC 60751 coefficients and the given resistance at 0 C.
+    pub const fn iec60751(r0: FixedPoint<unit::Ohm>) -> Self {
+        Self {
+            r0,
+            a: 3.9083e-3,
+            b: -5.775e-7,
+            c: -4.183e-12,
+        }
+    }
+
+    /// The resistance relative to r0 and its derivative at t.
+    fn relative(&self, t: Float) -> (Float, Float) {
+        let (mut value, mut slope) = (1.0 + self.a * t + self.b * t * t, self.a + 2.0 * self.b * t);
+        if t < 0.0 {
+            value += self.c * (t - 100.0) * t * t * t;
+            slope += self.c * (4.0 * t - 300.0) * t * t;
+        }
+        (value, slope)
+    }
+}
+
+#[cfg(feature = "float")]
+impl Sensor for CallendarVanDusen {
+    fn ohms(&self, celsius: Float) -> Float {
+        self.r0.to_float() * self.relative(celsius).0
+    }
+
+    fn celsius(&self, ohms: Float) -> Float {
+        // invert by Newton's method from the linear approximation
+        let target = ohms / self.r0.to_float();
+        let mut t = (target - 1.0) / self.a;
+        for _ in 0..NEWTON_STEPS {
+            let (value, slope) = self.relative(t);
+            t -= (value - target) / slope;
+        }
+        t
+    }
+}
+
+/// A fixed number of iterations so that results are deterministic.
+#[cfg(feature = "float")]
+const NEWTON_STEPS: usize = 8;
+
+/// Convert a float rounding to nearest.
+#[cfg(feature = "float")]
+fn round<R>(value: Float) -> FixedPoint<R>
+where
+    R: Spec,
+{
+    let scaled = value * R::SCALE;
+    let half = if scaled < 0.0 { -0.5 } else { 0.5 };
+    FixedPoint(R::from_fixed((scaled + half) as Fixed))
+}
+
+/// The natural logarithm of a positive number, without libm.
+/// Gives NaN for a value that is not positive or not finite.
+#[cfg(feature = "float")]
+fn ln(value: Float) -> Float {
+    if !value.is_finite() || value <= 0.0 {
+        return Float::NAN;
+    }
+
+    // reduce to m * 2^e with m in [1/sqrt(2), sqrt(2))
+    let (mut m, mut e) = (value, 0);
+    while m >= SQRT_2 {
+        m *= 0.5;
+        e += 1;
+    }
+    while m < FRAC_1_SQRT_2 {
+        m *= 2.0;
+        e -= 1;
+    }
+
+    // ln(m) = 2 atanh(s) with |s| < 0.18
+    let s = (m - 1.0) / (m + 1.0);
+    let s2 = s * s;
+    let series = 1.0 + s2 * (1.0 / 3.0 + s2 * (1.0 / 5.0 + s2 * (1.0 / 7.0 + s2 / 9.0)));
+    e as Float * LN_2 + 2.0 * s * series
+}
+
+/// The exponential function, without libm.
+#[cfg(feature = "float")]
+fn exp(value: Float) -> Float {
+    // beyond these bounds the result overflows or underflows,
+    // which also bounds the number of doublings or halvings below
+    if value > 89.0 {
+        return Float::INFINITY;
+    }
+    if value < -104.0 {
+        return 0.0;
+    }
+
+    // reduce to r + k ln(2) with |r| <= ln(2)/2
+    let k = value / LN_2;
+    let k = (k + if k < 0.0 { -0.5 } else { 0.5 }) as i32;
+    let r = value - k as Float * LN_2;
+
+    let mut term = 1.0;
+    let mut series = 1.0;
+    for n in 1..=8 {
+        term *= r / n as Float;
+        series += term;
+    }
+    let factor: Float = if k < 0 { 0.5 } else { 2.0 };
+    for _ in 0..k.unsigned_abs() {
+        series *= factor;
+    }
+    series
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Resistance = FixedPoint<unit::Ohm>;
+    type Temperature = FixedPoint<unit::Celsius>;
+
+    #[test]
+    fn divider() {
+        let reference = Resistance::with_fix2(1_000_000);
+        assert_eq!(
+            divider_resistance(Ratio::percent(50), reference, Divider::LowSide),
+            Some(reference)
+        );
+        assert_eq!(
+            divider_resistance(Ratio::percent(25), reference, Divider::HighSide),
+            Some(Resistance::with_fix2(3_000_000))
+        );
+        assert_eq!(
+            divider_resistance(Ratio::percent(25), reference, Divider::LowSide),
+            Some(Resistance::with_fix2(333_333))
+        );
+        assert_eq!(
+            divider_resistance(Ratio::ZERO, reference, Divider::LowSide),
+            None
+        );
+        assert_eq!(
+            divider_resistance(Ratio::ONE, reference, Divider::HighSide),
+            None
+        );
+    }
+
+    #[test]
+    fn platinum_curves() {
+        assert_eq!(PT100_CURVE.validate(), Ok(()));
+        assert_eq!(PT1000_CURVE.validate(), Ok(()));
+        assert_eq!(
+            PT100_CURVE.eval(Resistance::with_fix2(10000)),
+            Temperature::ZERO
+        );
+        assert_eq!(
+            PT1000_CURVE.eval(Resistance::with_fix2(138506)),
+            Temperature::with_fix2(10000)
+        );
+        // 37 C is 114.38 ohms
+        assert_eq!(
+            PT100_CURVE.eval(Resistance::with_fix2(11438)),
+            Temperature::with_fix2(3702)
+        );
+    }
+
+    #[test]
+    fn thermistor_curves() {
+        assert_eq!(NTC_10K_B3950_CURVE.validate(), Ok(()));
+        assert_eq!(NTC_10K_B3435_CURVE.validate(), Ok(()));
+        assert_eq!(
+            NTC_10K_B3950_CURVE.eval(Resistance::with_fix2(1_000_000)),
+            Temperature::with_fix2(2500)
+        );
+        assert_eq!(
+            NTC_10K_B3435_CURVE.eval(Resistance::with_fix2(2_870_429)),
+            Temperature::ZERO
+        );
+        assert_eq!(
+            NTC_10K_B3950_CURVE.eval(Resistance::with_fix2(100_000_000)),
+            Temperature::with_fix2(-4000)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn logarithms() {
+        for value in [1.0e-3, 0.5, 1.0, 2.0, 10.0, 3.3e6] {
+            assert!((ln(value) - value.ln()).abs() < 1.0e-6 * value.ln().abs().max(1.0));
+        }
+        for value in [-10.0, -1.0, 0.0, 0.3, 1.0, 5.0] {
+            assert!((exp(value) - value.exp()).abs() < 1.0e-6 * value.exp());
+        }
+        for value in [0.0, -1.0, Float::INFINITY, Float::NAN] {
+            assert!(ln(value).is_nan());
+        }
+        assert_eq!(exp(1.0e30), Float::INFINITY);
+        assert_eq!(exp(Float::NEG_INFINITY), 0.0);
+        assert!(exp(Float::NAN).is_nan());
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn beta() {
+        let sensor = Beta::new(
+            Resistance::with_fix2(1_000_000),
+            Temperature::with_fix2(2500),
+            3950.0,
+        )
+        .unwrap();
+        assert_eq!(
+            sensor.temperature(Resistance::with_fix2(1_000_000)),
+            Some(Temperature::with_fix2(2500))
+        );
+        assert_eq!(
+            sensor.resistance(Temperature::with_fix2(5000)),
+            Resistance::with_fix2(358_818)
+        );
+        let resistance = sensor.resistance(Temperature::with_fix2(-2000));
+        assert!((resistance.fix2() - 10_538_469).abs() < 100);
+        assert_eq!(sensor.temperature(Resistance::ZERO), None);
+        assert_eq!(
+            sensor.resistance(Temperature::with_fix2(-27315)),
+            Resistance::with_fix2(Fixed::MAX)
+        );
+
+        let invalid = Beta {
+            r0: Resistance::ZERO,
+            ..sensor
+        };
+        assert_eq!(invalid.temperature(Resistance::with_fix2(1_000_000)), None);
+        let invalid = Beta {
+            beta: 0.0,
+            ..sensor
+        };
+        assert_eq!(invalid.temperature(Resistance::with_fix2(1_000_000)), None);
+        assert_eq!(
+            Beta::new(Resistance::ZERO, sensor.t0, 3950.0),
+            Err(BetaError::ResistanceNotPositive)
+        );
+        for beta in [0.0, -3950.0, Float::NAN] {
+            assert_eq!(
+                Beta::new(sensor.r0, sensor.t0, beta),
+                Err(BetaError::BetaNotPositive)
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn beta_serialization() {
+        let text = r#"{"r0":1000000,"t0":2500,"beta":3950.0}"#;
+        let sensor: Beta = serde_json::from_str(text).unwrap();
+        assert_eq!(serde_json::to_string(&sensor).unwrap(), text);
+        assert!(serde_json::from_str::<Beta>(r#"{"r0":1000000,"t0":2500,"beta":0.0}"#).is_err());
+        assert!(serde_json::from_str::<Beta>(r#"{"r0":-1,"t0":2500,"beta":3950.0}"#).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn steinhart_hart() {
+        let sensor = SteinhartHart {
+            a: 1.009_25e-3,
+            b: 2.378_405e-4,
+            c: 2.019_203e-7,
+        };
+        assert_eq!(
+            sensor.temperature(Resistance::with_fix2(1_000_000)),
+            Some(Temperature::with_fix2(2468))
+        );
+        assert_eq!(
+            sensor.temperature(Resistance::with_fix2(358_800)),
+            Some(Temperature::with_fix2(5292))
+        );
+        let resistance = sensor.resistance(Temperature::with_fix2(5292));
+        assert!((resistance.fix2() - 358_800).abs() < 100);
+        assert!(sensor.celsius(0.0).is_nan());
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn callendar_van_dusen() {
+        let sensor = CallendarVanDusen::PT1000;
+        assert_eq!(
+            sensor.temperature(Resistance::with_fix2(138506)),
+            Some(Temperature::with_fix2(10000))
+        );
+        assert_eq!(
+            sensor.temperature(Resistance::with_fix2(80306)),
+            Some(Temperature::with_fix2(-5000))
+        );
+        assert_eq!(
+            sensor.resistance(Temperature::with_fix2(-2500)),
+            Resistance::with_fix2(90192)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn generated_curves() {
+        let curve = CallendarVanDusen::PT100
+            .curve(Temperature::with_fix2(-5000), Temperature::with_fix2(25000))
+            .unwrap();
+        assert_eq!(curve, PT100_CURVE);
+
+        let sensor = Beta::new(
+            Resistance::with_fix2(1_000_000),
+            Temperature::with_fix2(2500),
+            3950.0,
+        )
+        .unwrap();
+        let curve: Curve<unit::Ohm, unit::Celsius, 25> = sensor
+            .curve(Temperature::with_fix2(-2000), Temperature::with_fix2(10000))
+            .unwrap();
+        for ohms in [70_000, 358_818, 1_000_000, 2_000_000, 8_000_000] {
+            let resistance = Resistance::with_fix2(ohms);
+            let exact = sensor.temperature(resistance).unwrap();
+            assert!((curve.eval(resistance).fix2() - exact.fix2()).abs() < 25);
+        }
+
+        let curve: Curve<unit::Ohm, unit::Celsius, 34> = sensor
+            .curve(Temperature::with_fix2(-4000), Temperature::with_fix2(12500))
+            .unwrap();
+        for (generated, table) in curve.points().iter().zip(NTC_10K_B3950_CURVE.points()) {
+            assert_eq!(generated.1, table.1);
+            assert!((generated.0.fix2() - table.0.fix2()).abs() <= generated.0.fix2() / 100_000);
+        }
+    }
+}
@@ -0,0 +1,347 @@
+//! Three phase calculations on `PhasesOpt` quantities.
+//!
+//! Phases are assumed to be displaced by 120 degrees in the order
+//! L1, L2, L3 unless phase angles are given.  Angles are measured
+//! from any common reference.  Calculations use integer arithmetic only.
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    div_round, phases::PhasesOpt, ratio::Ratio, saturate, sqrt_round, unit, Fixed, FixedPoint,
+    Spec, Wide,
+};
+
+/// One in the fixed point representation of cosines and sines.
+const ONE: Wide = 1 << 30;
+
+/// The cosine of 120 degrees, exactly.
+const COS_120: Wide = -ONE / 2;
+
+/// Line to line quantities, such as voltages, for each pair of phases.
+/// A pair is None if either of its phases is out of service.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LineToLine<T> {
+    pub l1_l2: Option<T>,
+    pub l2_l3: Option<T>,
+    pub l3_l1: Option<T>,
+}
+
+impl<R> LineToLine<FixedPoint<R>>
+where
+    R: Spec,
+{
+    /// The voltage unbalance factor defined by IEC 61000-4-30, the ratio
+    /// of the negative to the positive sequence voltage, computed from the
+    /// line to line magnitudes.  None if a pair is missing, all are zero
+    /// or the magnitudes cannot form a triangle.
+    pub fn unbalance_factor(&self) -> Option<Ratio> {
+        let sides =
+            [self.l1_l2?, self.l2_l3?, self.l3_l1?].map(|side| (side.0.to_fixed() as i128).abs());
+
+        // reduce to 16 bits so that fourth powers fit
+        let bits = 128 - sides.iter().max().copied().unwrap_or(0).leading_zeros();
+        let shift = bits.saturating_sub(16);
+        let sides = sides.map(|side| (side + ((1 << shift) >> 1)) >> shift);
+
+        // u = 6 * sum(a^4) / sum(a^2)^2 - 2 is zero when balanced
+        let squares: i128 = sides.iter().map(|side| side * side).sum();
+        let fourths: i128 = sides.iter().map(|side| (side * side).pow(2)).sum();
+        let denom = squares * squares;
+        let numer = 6 * fourths - 2 * denom;
+        if denom == 0 || numer > denom {
+            return None;
+        }
+        // the factor is sqrt(u) / (1 + sqrt(1 - u)) with u in Q56
+        let u = ((numer << 56) / denom) as u64;
+        let factor = div_round(
+            sqrt_round(u) as Wide * unit::Ratio::DENOM,
+            (1 << 28) + sqrt_round((1 << 56) - u) as Wide,
+        );
+        Some(Ratio::with_fix4(saturate(factor)))
+    }
+}
+
+/// The line to line voltages given phase to neutral voltages
+/// displaced by 120 degrees.
+pub fn line_to_line<R>(voltage: PhasesOpt<FixedPoint<R>>) -> LineToLine<FixedPoint<R>>
+where
+    R: Spec,
+{
+    let pair =
+        |a: Option<FixedPoint<R>>, b: Option<FixedPoint<R>>| Some(difference(a?, b?, COS_120));
+    LineToLine {
+        l1_l2: pair(voltage.0, voltage.1),
+        l2_l3: pair(voltage.1, voltage.2),
+        l3_l1: pair(voltage.2, voltage.0),
+    }
+}
+
+/// The line to line voltages given phase to neutral voltages and their
+/// phase angles.  A pair is None if an angle is missing.
+pub fn line_to_line_with_angles<R>(
+    voltage: PhasesOpt<FixedPoint<R>>,
+    angle: PhasesOpt<FixedPoint<unit::Degree>>,
+) -> LineToLine<FixedPoint<R>>
+where
+    R: Spec,
+{
+    let pair = |a: Option<FixedPoint<R>>,
+                b: Option<FixedPoint<R>>,
+                angle_a: Option<FixedPoint<unit::Degree>>,
+                angle_b: Option<FixedPoint<unit::Degree>>| {
+        let between = angle_a?.0.to_fixed() as Wide - angle_b?.0.to_fixed() as Wide;
+        Some(difference(a?, b?, cos_sin(between).0))
+    };
+    LineToLine {
+        l1_l2: pair(voltage.0, voltage.1, angle.0, angle.1),
+        l2_l3: pair(voltage.1, voltage.2, angle.1, angle.2),
+        l3_l1: pair(voltage.2, voltage.0, angle.2, angle.0),
+    }
+}
+
+/// The neutral current given phase currents displaced by 120 degrees.
+/// A phase out of service carries no current.
+pub fn neutral_current<R>(current: PhasesOpt<FixedPoint<R>>) -> FixedPoint<R>
+where
+    R: Spec,
+{
+    let [a, b, c] = <[Option<FixedPoint<R>>; 3]>::from(current)
+        .map(|phase| phase.map_or(0, |value| value.0.to_fixed() as i128));
+    let square = a * a + b * b + c * c - a * b - b * c - c * a;
+    FixedPoint(R::from_fixed(root(square)))
+}
+
+/// The neutral current given phase currents and their phase angles.
+/// A phase contributes only if both its current and angle are given.
+pub fn neutral_current_with_angles<R>(
+    current: PhasesOpt<FixedPoint<R>>,
+    angle: PhasesOpt<FixedPoint<unit::Degree>>,
+) -> FixedPoint<R>
+where
+    R: Spec,
+{
+    let (mut x, mut y) = (0i128, 0i128);
+    let current: [Option<FixedPoint<R>>; 3] = current.into();
+    let angle: [Option<FixedPoint<unit::Degree>>; 3] = angle.into();
+    for (value, angle) in current.into_iter().zip(angle) {
+        if let (Some(value), Some(angle)) = (value, angle) {
+            let value = value.0.to_fixed() as i128;
+            let (cos, sin) = cos_sin(angle.0.to_fixed() as Wide);
+            x += value * cos as i128;
+            y += value * sin as i128;
+        }
+    }
+    let square = (x * x + y * y + (1 << 59)) >> 60;
+    FixedPoint(R::from_fixed(root(square)))
+}
+
+/// The magnitude of the difference of two phasors with magnitudes
+/// `a` and `b` given the cosine of the angle between them,
+/// `sqrt(a^2 + b^2 - 2ab cos)`, rounded to nearest.
+fn difference<R>(a: FixedPoint<R>, b: FixedPoint<R>, cos: Wide) -> FixedPoint<R>
+where
+    R: Spec,
+{
+    let (a, b) = (a.0.to_fixed() as i128, b.0.to_fixed() as i128);
+    let cross = (2 * a * b * cos as i128 + ONE as i128 / 2) >> 30;
+    FixedPoint(R::from_fixed(root(a * a + b * b - cross)))
+}
+
+/// The square root of a non-negative square in fixed units,
+/// rounded to nearest and saturated.
+fn root(square: i128) -> Fixed {
+    let square = square.clamp(0, u64::MAX as i128) as u64;
+    saturate(sqrt_round(square) as Wide)
+}
+
+/// The cosine and sine of an angle in hundredths of a degree,
+/// scaled by `ONE`, within one part in 2^29.
+fn cos_sin(angle: Wide) -> (Wide, Wide) {
+    const RIGHT: Wide = 90 * unit::Degree::DENOM;
+    // pi scaled by ONE
+    const PI: Wide = 3_373_259_426;
+
+    let angle = angle.rem_euclid(4 * RIGHT);
+    let (quadrant, within) = (angle / RIGHT, angle % RIGHT);
+
+    // evaluate the series to x^11 within 45 degrees of zero
+    let (within, complement) = if 2 * within > RIGHT {
+        (RIGHT - within, true)
+    } else {
+        (within, false)
+    };
+    let x = within * PI / (2 * RIGHT);
+    let x2 = (x * x) >> 30;
+    let mut cos = ONE;
+    let mut sin = x;
+    let (mut even, mut odd) = (ONE, x);
+    for n in 1..=5 {
+        even = -((even * x2) >> 30) / ((2 * n - 1) * (2 * n));
+        odd = -((odd * x2) >> 30) / ((2 * n) * (2 * n + 1));
+        cos += even;
+        sin += odd;
+    }
+    let (cos, sin) = if complement { (sin, cos) } else { (cos, sin) };
+
+    match quadrant {
+        0 => (cos, sin),
+        1 => (-sin, cos),
+        2 => (-cos, -sin),
+        _ => (sin, -cos),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Current = FixedPoint<unit::Amp>;
+    type Voltage = FixedPoint<unit::Volt>;
+    type Angle = FixedPoint<unit::Degree>;
+
+    fn balanced() -> PhasesOpt<Angle> {
+        PhasesOpt(
+            Some(Angle::ZERO),
+            Some(Angle::with_fix2(-12000)),
+            Some(Angle::with_fix2(12000)),
+        )
+    }
+
+    #[test]
+    fn trigonometry() {
+        for angle in (-72000..72000).step_by(1234) {
+            let (cos, sin) = cos_sin(angle);
+            let radians = (angle as f64 / 100.0).to_radians();
+            assert!((cos as f64 / ONE as f64 - radians.cos()).abs() < 2.0e-9);
+            assert!((sin as f64 / ONE as f64 - radians.sin()).abs() < 2.0e-9);
+        }
+        assert_eq!(cos_sin(9000), (0, ONE));
+        assert_eq!(cos_sin(-18000), (-ONE, 0));
+    }
+
+    #[test]
+    fn balanced_line_to_line() {
+        let voltage = PhasesOpt(
+            Some(Voltage::with_fix1(2300)),
+            Some(Voltage::with_fix1(2300)),
+            Some(Voltage::with_fix1(2400)),
+        );
+        let expected = LineToLine {
+            l1_l2: Some(Voltage::with_fix1(3984)),
+            l2_l3: Some(Voltage::with_fix1(4071)),
+            l3_l1: Some(Voltage::with_fix1(4071)),
+        };
+        assert_eq!(line_to_line(voltage), expected);
+        assert_eq!(line_to_line_with_angles(voltage, balanced()), expected);
+        assert_eq!(expected.unbalance_factor(), Some(Ratio::with_fix4(143)));
+    }
+
+    #[test]
+    fn unbalance_factor() {
+        let sides = |a, b, c| LineToLine {
+            l1_l2: Some(Voltage::with_fix1(a)),
+            l2_l3: Some(Voltage::with_fix1(b)),
+            l3_l1: Some(Voltage::with_fix1(c)),
+        };
+        assert_eq!(
+            sides(4000, 4000, 4000).unbalance_factor(),
+            Some(Ratio::ZERO)
+        );
+        assert_eq!(
+            sides(4000, 4000, 3800).unbalance_factor(),
+            Some(Ratio::with_fix4(336))
+        );
+        // large magnitudes are reduced before taking fourth powers
+        assert_eq!(
+            sides(400_000_000, 400_000_000, 380_000_000).unbalance_factor(),
+            Some(Ratio::with_fix4(336))
+        );
+        assert_eq!(sides(10, 10, 20).unbalance_factor(), Some(Ratio::ONE));
+        assert_eq!(sides(10, 10, 30).unbalance_factor(), None);
+        assert_eq!(sides(0, 0, 0).unbalance_factor(), None);
+        let missing = LineToLine {
+            l1_l2: None,
+            ..sides(4000, 4000, 4000)
+        };
+        assert_eq!(missing.unbalance_factor(), None);
+    }
+
+    #[test]
+    fn missing_phase() {
+        let voltage = PhasesOpt(
+            Some(Voltage::with_fix1(2300)),
+            None,
+            Some(Voltage::with_fix1(2300)),
+        );
+        assert_eq!(
+            line_to_line(voltage),
+            LineToLine {
+                l1_l2: None,
+                l2_l3: None,
+                l3_l1: Some(Voltage::with_fix1(3984)),
+            }
+        );
+    }
+
+    #[test]
+    fn angles() {
+        // a split phase supply has phases 180 degrees apart
+        let voltage = PhasesOpt(
+            Some(Voltage::with_fix1(1200)),
+            Some(Voltage::with_fix1(1200)),
+            None,
+        );
+        let angle = PhasesOpt(Some(Angle::ZERO), Some(Angle::with_fix2(18000)), None);
+        assert_eq!(
+            line_to_line_with_angles(voltage, angle).l1_l2,
+            Some(Voltage::with_fix1(2400))
+        );
+        let angle = PhasesOpt(Some(Angle::ZERO), Some(Angle::with_fix2(-9000)), None);
+        assert_eq!(
+            line_to_line_with_angles(voltage, angle).l1_l2,
+            Some(Voltage::with_fix1(1697))
+        );
+        let angle = PhasesOpt(Some(Angle::ZERO), None, None);
+        assert_eq!(line_to_line_with_angles(voltage, angle).l1_l2, None);
+    }
+
+    #[test]
+    fn neutral() {
+        let balanced_current = PhasesOpt(
+            Some(Current::with_fix1(160)),
+            Some(Current::with_fix1(160)),
+            Some(Current::with_fix1(160)),
+        );
+        assert_eq!(neutral_current(balanced_current), Current::ZERO);
+        assert_eq!(
+            neutral_current_with_angles(balanced_current, balanced()),
+            Current::ZERO
+        );
+
+        // single phase charging on L2 returns entirely through the neutral
+        let single = PhasesOpt(None, Some(Current::with_fix1(320)), None);
+        assert_eq!(neutral_current(single), Current::with_fix1(320));
+        assert_eq!(
+            neutral_current_with_angles(single, balanced()),
+            Current::with_fix1(320)
+        );
+
+        let current = PhasesOpt(
+            Some(Current::with_fix1(100)),
+            Some(Current::with_fix1(200)),
+            Some(Current::with_fix1(300)),
+        );
+        assert_eq!(neutral_current(current), Current::with_fix1(173));
+        assert_eq!(
+            neutral_current_with_angles(current, balanced()),
+            Current::with_fix1(173)
+        );
+
+        // split phase currents are opposed
+        let angle = PhasesOpt(Some(Angle::ZERO), Some(Angle::with_fix2(18000)), None);
+        assert_eq!(
+            neutral_current_with_angles(current, angle),
+            Current::with_fix1(100)
+        );
+    }
+}
@@ -0,0 +1,404 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{phases::PhasesOpt, FixedPoint, Spec};
+
+/// The side of a threshold on which an alarm is raised.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Direction {
+    /// Raise the alarm at or above the set value, such as for over-voltage.
+    Above,
+    /// Raise the alarm at or below the set value, such as for under-voltage.
+    Below,
+}
+
+/// A change of alarm state.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Edge {
+    /// The alarm was raised.
+    Rising,
+    /// The alarm was cleared.
+    Falling,
+}
+
+/// The configuration of an alarm threshold with hysteresis.
+///
+/// The alarm is raised once the value has been at or beyond `set`
+/// for `delay`, measured in the same units as the elapsed times
+/// given to `Hysteresis::update`.  It is cleared when the value
+/// returns to or beyond `clear`.
+///
+/// The clear value must not be beyond the set value in the alarm
+/// direction, which is checked by `new`, by `Hysteresis::new`
+/// and when a threshold is deserialized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[serde(bound = "", try_from = "RawThreshold<R>")]
+pub struct Threshold<R>
+where
+    R: Spec,
+{
+    pub set: FixedPoint<R>,
+    pub clear: FixedPoint<R>,
+    pub direction: Direction,
+    pub delay: u32,
+}
+
+/// The reason a threshold is invalid.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ThresholdError {
+    /// The clear value is beyond the set value in the alarm direction.
+    ClearBeyondSet,
+}
+
+impl fmt::Display for ThresholdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThresholdError::ClearBeyondSet => {
+                write!(
+                    f,
+                    "a threshold clear value must not be beyond its set value"
+                )
+            }
+        }
+    }
+}
+
+impl<R> Threshold<R>
+where
+    R: Spec,
+{
+    /// A threshold that trips without delay.
+    pub fn new(
+        set: FixedPoint<R>,
+        clear: FixedPoint<R>,
+        direction: Direction,
+    ) -> Result<Self, ThresholdError> {
+        let threshold = Self {
+            set,
+            clear,
+            direction,
+            delay: 0,
+        };
+        threshold.validate()?;
+        Ok(threshold)
+    }
+
+    /// The same threshold with a delay before tripping.
+    pub const fn with_delay(self, delay: u32) -> Self {
+        Self { delay, ..self }
+    }
+
+    /// Check that the clear value is on the safe side of the set value.
+    pub fn validate(&self) -> Result<(), ThresholdError> {
+        let (set, clear) = (self.set.0.to_fixed(), self.clear.0.to_fixed());
+        let valid = match self.direction {
+            Direction::Above => clear <= set,
+            Direction::Below => clear >= set,
+        };
+        if !valid {
+            return Err(ThresholdError::ClearBeyondSet);
+        }
+        Ok(())
+    }
+
+    fn is_set(&self, value: FixedPoint<R>) -> bool {
+        let (value, set) = (value.0.to_fixed(), self.set.0.to_fixed());
+        match self.direction {
+            Direction::Above => value >= set,
+            Direction::Below => value <= set,
+        }
+    }
+
+    fn is_clear(&self, value: FixedPoint<R>) -> bool {
+        let (value, clear) = (value.0.to_fixed(), self.clear.0.to_fixed());
+        match self.direction {
+            Direction::Above => value <= clear,
+            Direction::Below => value >= clear,
+        }
+    }
+}
+
+/// The unvalidated serialized form of a Threshold.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct RawThreshold<R>
+where
+    R: Spec,
+{
+    set: FixedPoint<R>,
+    clear: FixedPoint<R>,
+    direction: Direction,
+    delay: u32,
+}
+
+impl<R> TryFrom<RawThreshold<R>> for Threshold<R>
+where
+    R: Spec,
+{
+    type Error = ThresholdError;
+
+    fn try_from(value: RawThreshold<R>) -> Result<Self, ThresholdError> {
+        Ok(Self::new(value.set, value.clear, value.direction)?.with_delay(value.delay))
+    }
+}
+
+/// The state of an alarm with a `Threshold`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Hysteresis<R>
+where
+    R: Spec,
+{
+    threshold: Threshold<R>,
+    active: bool,
+    pending: Option<u32>,
+}
+
+impl<R> Hysteresis<R>
+where
+    R: Spec,
+{
+    /// An inactive alarm, if the threshold is valid.
+    pub fn new(threshold: Threshold<R>) -> Result<Self, ThresholdError> {
+        threshold.validate()?;
+        Ok(Self {
+            threshold,
+            active: false,
+            pending: None,
+        })
+    }
+
+    pub fn threshold(&self) -> &Threshold<R> {
+        &self.threshold
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Clear the alarm without an edge.
+    pub fn reset(&mut self) {
+        self.active = false;
+        self.pending = None;
+    }
+
+    /// Accept the next value, given the time elapsed since the previous value,
+    /// and give the edge if the alarm state changes.
+    pub fn update(&mut self, value: FixedPoint<R>, elapsed: u32) -> Option<Edge> {
+        if self.active {
+            if self.threshold.is_clear(value) {
+                self.active = false;
+                return Some(Edge::Falling);
+            }
+        } else if self.threshold.is_set(value) {
+            // the delay is timed from the first value beyond the set value
+            let pending = self
+                .pending
+                .map_or(0, |pending| pending.saturating_add(elapsed));
+            if pending >= self.threshold.delay {
+                self.active = true;
+                self.pending = None;
+                return Some(Edge::Rising);
+            }
+            self.pending = Some(pending);
+        } else {
+            self.pending = None;
+        }
+        None
+    }
+}
+
+/// An alarm for each phase of a `PhasesOpt` series with a common threshold.
+/// When a phase is out of service its alarm is cleared.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PhasesHysteresis<R>(pub Hysteresis<R>, pub Hysteresis<R>, pub Hysteresis<R>)
+where
+    R: Spec;
+
+impl<R> PhasesHysteresis<R>
+where
+    R: Spec,
+{
+    /// Inactive alarms, if the threshold is valid.
+    pub fn new(threshold: Threshold<R>) -> Result<Self, ThresholdError> {
+        let alarm = Hysteresis::new(threshold)?;
+        Ok(Self(alarm, alarm, alarm))
+    }
+
+    /// True if the alarm is active on any phase.
+    pub fn is_active(&self) -> bool {
+        self.0.is_active() || self.1.is_active() || self.2.is_active()
+    }
+
+    pub fn reset(&mut self) {
+        self.0.reset();
+        self.1.reset();
+        self.2.reset();
+    }
+
+    /// Accept the next values and give the edge of each phase whose alarm
+    /// state changes.
+    pub fn update(&mut self, value: PhasesOpt<FixedPoint<R>>, elapsed: u32) -> PhasesOpt<Edge> {
+        PhasesOpt(
+            update_opt(&mut self.0, value.0, elapsed),
+            update_opt(&mut self.1, value.1, elapsed),
+            update_opt(&mut self.2, value.2, elapsed),
+        )
+    }
+}
+
+fn update_opt<R>(
+    alarm: &mut Hysteresis<R>,
+    value: Option<FixedPoint<R>>,
+    elapsed: u32,
+) -> Option<Edge>
+where
+    R: Spec,
+{
+    match value {
+        Some(value) => alarm.update(value, elapsed),
+        None => {
+            let active = alarm.is_active();
+            alarm.reset();
+            active.then_some(Edge::Falling)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{unit, Fixed};
+
+    type Voltage = FixedPoint<unit::Volt>;
+
+    fn over_voltage_threshold() -> Threshold<unit::Volt> {
+        Threshold::new(
+            Voltage::with_fix1(2530),
+            Voltage::with_fix1(2480),
+            Direction::Above,
+        )
+        .unwrap()
+    }
+
+    fn run(alarm: &mut Hysteresis<unit::Volt>, values: &[Fixed]) -> Vec<Option<Edge>> {
+        values
+            .iter()
+            .map(|value| alarm.update(Voltage::with_fix1(*value), 100))
+            .collect()
+    }
+
+    #[test]
+    fn over_voltage() {
+        let mut alarm = Hysteresis::new(over_voltage_threshold()).unwrap();
+        assert_eq!(
+            run(&mut alarm, &[2400, 2530, 2500, 2490, 2480, 2550]),
+            [
+                None,
+                Some(Edge::Rising),
+                None,
+                None,
+                Some(Edge::Falling),
+                Some(Edge::Rising)
+            ]
+        );
+        assert!(alarm.is_active());
+        alarm.reset();
+        assert!(!alarm.is_active());
+    }
+
+    #[test]
+    fn delay() {
+        let mut alarm = Hysteresis::new(over_voltage_threshold().with_delay(200)).unwrap();
+        assert_eq!(
+            run(&mut alarm, &[2540, 2540, 2520, 2540, 2540, 2540]),
+            [None, None, None, None, None, Some(Edge::Rising)]
+        );
+    }
+
+    #[test]
+    fn under_voltage() {
+        let threshold = Threshold::new(
+            Voltage::with_fix1(2070),
+            Voltage::with_fix1(2100),
+            Direction::Below,
+        )
+        .unwrap();
+        let mut alarm = Hysteresis::new(threshold).unwrap();
+        assert_eq!(
+            run(&mut alarm, &[2300, 2070, 2090, 2100, 2080, 1000]),
+            [
+                None,
+                Some(Edge::Rising),
+                None,
+                Some(Edge::Falling),
+                None,
+                Some(Edge::Rising)
+            ]
+        );
+    }
+
+    #[test]
+    fn reversed() {
+        let (set, clear) = (Voltage::with_fix1(2480), Voltage::with_fix1(2530));
+        assert_eq!(
+            Threshold::new(set, clear, Direction::Above),
+            Err(ThresholdError::ClearBeyondSet)
+        );
+        assert!(Threshold::new(clear, set, Direction::Below).is_err());
+        assert!(Threshold::new(set, set, Direction::Above).is_ok());
+        let reversed = Threshold {
+            set,
+            clear,
+            direction: Direction::Above,
+            delay: 0,
+        };
+        assert_eq!(
+            Hysteresis::new(reversed),
+            Err(ThresholdError::ClearBeyondSet)
+        );
+        assert!(PhasesHysteresis::new(reversed).is_err());
+    }
+
+    #[test]
+    fn phases() {
+        let mut alarm = PhasesHysteresis::new(over_voltage_threshold()).unwrap();
+        let (high, low) = (
+            Some(Voltage::with_fix1(2540)),
+            Some(Voltage::with_fix1(2300)),
+        );
+        assert_eq!(
+            alarm.update(PhasesOpt(high, low, high), 100),
+            PhasesOpt(Some(Edge::Rising), None, Some(Edge::Rising))
+        );
+        assert_eq!(
+            alarm.update(PhasesOpt(low, high, None), 100),
+            PhasesOpt(Some(Edge::Falling), Some(Edge::Rising), Some(Edge::Falling))
+        );
+        assert!(alarm.is_active());
+    }
+
+    #[test]
+    fn serialization() {
+        let threshold = over_voltage_threshold().with_delay(5);
+        let text = serde_json::to_string(&threshold).unwrap();
+        assert_eq!(
+            text,
+            r#"{"set":2530,"clear":2480,"direction":"Above","delay":5}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Threshold<unit::Volt>>(&text).unwrap(),
+            threshold
+        );
+        assert!(serde_json::from_str::<Threshold<unit::Volt>>(
+            r#"{"set":2480,"clear":2530,"direction":"Above","delay":5}"#
+        )
+        .is_err());
+    }
+}
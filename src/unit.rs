@@ -1,10 +1,12 @@
-use crate::{Fixed, Float, Spec};
+use crate::{qformat::QFormat, quantity, Fixed, Spec, Wide};
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Volt(pub Fixed);
 impl Spec for Volt {
-    const SCALE: Float = 10.0;
+    type Quantity = quantity::Voltage;
+    const DENOM: Wide = 10;
     const SYMBOL: &'static str = "V";
 
     fn to_fixed(self) -> Fixed {
@@ -15,10 +17,12 @@ impl Spec for Volt {
     }
 }
 
-#[derive(Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct PreciseVolt(pub Fixed);
 impl Spec for PreciseVolt {
-    const SCALE: Float = 1000.0;
+    type Quantity = quantity::Voltage;
+    const DENOM: Wide = 1000;
     const SYMBOL: &'static str = "V";
 
     fn to_fixed(self) -> Fixed {
@@ -29,10 +33,12 @@ impl Spec for PreciseVolt {
     }
 }
 
-#[derive(Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Amp(pub Fixed);
 impl Spec for Amp {
-    const SCALE: Float = 10.0;
+    type Quantity = quantity::Current;
+    const DENOM: Wide = 10;
     const SYMBOL: &'static str = "A";
 
     fn to_fixed(self) -> Fixed {
@@ -43,10 +49,12 @@ impl Spec for Amp {
     }
 }
 
-#[derive(Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Watt(pub Fixed);
 impl Spec for Watt {
-    const SCALE: Float = 1.0;
+    type Quantity = quantity::Power;
+    const DENOM: Wide = 1;
     const SYMBOL: &'static str = "W";
 
     fn to_fixed(self) -> Fixed {
@@ -57,10 +65,13 @@ impl Spec for Watt {
     }
 }
 
-#[derive(Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct KiloWatt(pub Fixed);
 impl Spec for KiloWatt {
-    const SCALE: Float = 10.0;
+    type Quantity = quantity::Power;
+    const DENOM: Wide = 10;
+    const PREFIX: i32 = 3;
     const SYMBOL: &'static str = "kW";
 
     fn to_fixed(self) -> Fixed {
@@ -71,10 +82,13 @@ impl Spec for KiloWatt {
     }
 }
 
-#[derive(Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct KiloWattHour(pub Fixed);
 impl Spec for KiloWattHour {
-    const SCALE: Float = 100.0;
+    type Quantity = quantity::Energy;
+    const DENOM: Wide = 100;
+    const PREFIX: i32 = 3;
     const SYMBOL: &'static str = "kWh";
 
     fn to_fixed(self) -> Fixed {
@@ -85,10 +99,12 @@ impl Spec for KiloWattHour {
     }
 }
 
-#[derive(Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Celsius(pub Fixed);
 impl Spec for Celsius {
-    const SCALE: Float = 100.0;
+    type Quantity = quantity::Temperature;
+    const DENOM: Wide = 100;
     const SYMBOL: &'static str = "C";
 
     fn to_fixed(self) -> Fixed {
@@ -98,3 +114,148 @@ impl Spec for Celsius {
         Self(fixed)
     }
 }
+
+/// An angle, such as a phase angle, in degrees.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Degree(pub Fixed);
+impl Spec for Degree {
+    type Quantity = quantity::Angle;
+    const DENOM: Wide = 100;
+    const SYMBOL: &'static str = "deg";
+
+    fn to_fixed(self) -> Fixed {
+        self.0
+    }
+    fn from_fixed(fixed: Fixed) -> Self {
+        Self(fixed)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ohm(pub Fixed);
+impl Spec for Ohm {
+    type Quantity = quantity::Resistance;
+    const DENOM: Wide = 100;
+    const SYMBOL: &'static str = "Ohm";
+
+    fn to_fixed(self) -> Fixed {
+        self.0
+    }
+    fn from_fixed(fixed: Fixed) -> Self {
+        Self(fixed)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Percent(pub Fixed);
+impl Spec for Percent {
+    type Quantity = quantity::Dimensionless;
+    const DENOM: Wide = 10;
+    const PREFIX: i32 = -2;
+    const SYMBOL: &'static str = "%";
+
+    fn to_fixed(self) -> Fixed {
+        self.0
+    }
+    fn from_fixed(fixed: Fixed) -> Self {
+        Self(fixed)
+    }
+}
+
+/// A dimensionless ratio with four decimal places.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ratio(pub Fixed);
+impl Spec for Ratio {
+    type Quantity = quantity::Dimensionless;
+    const DENOM: Wide = 10000;
+    const SYMBOL: &'static str = "";
+
+    fn to_fixed(self) -> Fixed {
+        self.0
+    }
+    fn from_fixed(fixed: Fixed) -> Self {
+        Self(fixed)
+    }
+}
+
+/// A dimensionless ratio with six decimal places,
+/// for small gains and factors.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PreciseRatio(pub Fixed);
+impl Spec for PreciseRatio {
+    type Quantity = quantity::Dimensionless;
+    const DENOM: Wide = 1_000_000;
+    const SYMBOL: &'static str = "";
+
+    fn to_fixed(self) -> Fixed {
+        self.0
+    }
+    fn from_fixed(fixed: Fixed) -> Self {
+        Self(fixed)
+    }
+}
+
+/// Q15 format: a signed fraction in the range [-1, 1) with 15 fraction bits.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Q15(pub i16);
+impl Spec for Q15 {
+    type Quantity = quantity::Dimensionless;
+    const DENOM: Wide = 1 << 15;
+    const SYMBOL: &'static str = "";
+
+    fn to_fixed(self) -> Fixed {
+        self.0 as Fixed
+    }
+    fn from_fixed(fixed: Fixed) -> Self {
+        Self(fixed.clamp(i16::MIN as Fixed, i16::MAX as Fixed) as i16)
+    }
+}
+impl QFormat for Q15 {
+    const BITS: u32 = 15;
+}
+
+/// Q16.16 format: a signed number with 16 integer and 16 fraction bits.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Q16_16(pub i32);
+impl Spec for Q16_16 {
+    type Quantity = quantity::Dimensionless;
+    const DENOM: Wide = 1 << 16;
+    const SYMBOL: &'static str = "";
+
+    fn to_fixed(self) -> Fixed {
+        self.0
+    }
+    fn from_fixed(fixed: Fixed) -> Self {
+        Self(fixed)
+    }
+}
+impl QFormat for Q16_16 {
+    const BITS: u32 = 16;
+}
+
+/// Q1.31 format: a signed fraction in the range [-1, 1) with 31 fraction bits.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Q31(pub i32);
+impl Spec for Q31 {
+    type Quantity = quantity::Dimensionless;
+    const DENOM: Wide = 1 << 31;
+    const SYMBOL: &'static str = "";
+
+    fn to_fixed(self) -> Fixed {
+        self.0
+    }
+    fn from_fixed(fixed: Fixed) -> Self {
+        Self(fixed)
+    }
+}
+impl QFormat for Q31 {
+    const BITS: u32 = 31;
+}
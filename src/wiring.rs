@@ -0,0 +1,222 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::phases::{Phase, PhasesOpt};
+
+/// How the phases of a device, such as a charger, connect to the
+/// phases of the site.
+///
+/// `apply` converts a quantity from the device frame to the site frame
+/// and `invert` converts from the site frame to the device frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PhaseMapping {
+    /// Device phases L1, L2 and L3 connect to the given site phases in turn.
+    Permutation(Permutation),
+    /// A single phase device whose L1 connects to the given site phase.
+    Single(Phase),
+}
+
+/// The site phases to which device phases L1, L2 and L3 connect in turn,
+/// with each site phase connected once.
+///
+/// A permutation can only be constructed with `new`, which checks it,
+/// and is validated when deserialized.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[serde(try_from = "[Phase; 3]")]
+pub struct Permutation([Phase; 3]);
+
+/// The reason a phase mapping is invalid.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MappingError {
+    /// A site phase is connected to more than one device phase.
+    Repeated(Phase),
+}
+
+impl fmt::Display for MappingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingError::Repeated(phase) => {
+                write!(f, "site phase {phase:?} is connected more than once")
+            }
+        }
+    }
+}
+
+impl Permutation {
+    /// Device phases connect to the same site phases.
+    pub const IDENTITY: Self = Self(Phase::ALL);
+
+    /// Construct from the site phases, checking that no site phase
+    /// is connected more than once.
+    pub const fn new(phases: [Phase; 3]) -> Result<Self, MappingError> {
+        let [a, b, c] = phases;
+        if a as u8 == b as u8 || a as u8 == c as u8 {
+            return Err(MappingError::Repeated(a));
+        }
+        if b as u8 == c as u8 {
+            return Err(MappingError::Repeated(b));
+        }
+        Ok(Self(phases))
+    }
+
+    /// The site phases of device phases L1, L2 and L3.
+    pub const fn phases(&self) -> [Phase; 3] {
+        self.0
+    }
+
+    /// The permutation from the site frame to the device frame.
+    pub fn inverse(&self) -> Self {
+        let mut inverse = Phase::ALL;
+        for (device, site) in Phase::ALL.into_iter().zip(self.0) {
+            inverse[site as usize] = device;
+        }
+        Self(inverse)
+    }
+}
+
+impl TryFrom<[Phase; 3]> for Permutation {
+    type Error = MappingError;
+
+    fn try_from(value: [Phase; 3]) -> Result<Self, MappingError> {
+        Self::new(value)
+    }
+}
+
+impl PhaseMapping {
+    /// Device phases connect to the same site phases.
+    pub const IDENTITY: Self = Self::Permutation(Permutation::IDENTITY);
+
+    /// Construct a permutation with validation.
+    pub fn permutation(phases: [Phase; 3]) -> Result<Self, MappingError> {
+        Ok(Self::Permutation(Permutation::new(phases)?))
+    }
+
+    /// The mapping from the site frame to the device frame,
+    /// or None for a single phase mapping.
+    pub fn inverse(&self) -> Option<Self> {
+        match self {
+            Self::Permutation(permutation) => Some(Self::Permutation(permutation.inverse())),
+            Self::Single(_) => None,
+        }
+    }
+
+    /// Convert a quantity from the device frame to the site frame.
+    /// For a single phase mapping only device phase L1 is used.
+    pub fn apply<T>(&self, device: PhasesOpt<T>) -> PhasesOpt<T> {
+        let mut site = PhasesOpt(None, None, None);
+        match *self {
+            Self::Permutation(Permutation(phases)) => {
+                let [a, b, c]: [Option<T>; 3] = device.into();
+                site[phases[0]] = a;
+                site[phases[1]] = b;
+                site[phases[2]] = c;
+            }
+            Self::Single(phase) => site[phase] = device.0,
+        }
+        site
+    }
+
+    /// Convert a quantity from the site frame to the device frame.
+    /// For a single phase mapping only device phase L1 is in service.
+    pub fn invert<T>(&self, mut site: PhasesOpt<T>) -> PhasesOpt<T> {
+        match *self {
+            Self::Permutation(Permutation(phases)) => {
+                PhasesOpt::from_fn(|phase| site[phases[phase as usize]].take())
+            }
+            Self::Single(phase) => PhasesOpt(site[phase].take(), None, None),
+        }
+    }
+}
+
+impl Default for PhaseMapping {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{unit, FixedPoint};
+
+    type Current = FixedPoint<unit::Amp>;
+
+    const ROTATED: PhaseMapping = match Permutation::new([Phase::L2, Phase::L3, Phase::L1]) {
+        Ok(permutation) => PhaseMapping::Permutation(permutation),
+        Err(_) => panic!("a rotation is a permutation"),
+    };
+
+    fn currents() -> PhasesOpt<Current> {
+        PhasesOpt(
+            Some(Current::with_fix1(100)),
+            Some(Current::with_fix1(200)),
+            None,
+        )
+    }
+
+    #[test]
+    fn rotation() {
+        let site = ROTATED.apply(currents());
+        assert_eq!(
+            site,
+            PhasesOpt(
+                None,
+                Some(Current::with_fix1(100)),
+                Some(Current::with_fix1(200))
+            )
+        );
+        assert_eq!(ROTATED.invert(site), currents());
+        assert_eq!(ROTATED.inverse().unwrap().apply(site), currents());
+        assert_eq!(PhaseMapping::IDENTITY.apply(currents()), currents());
+    }
+
+    #[test]
+    fn single() {
+        let mapping = PhaseMapping::Single(Phase::L3);
+        let site = mapping.apply(currents());
+        assert_eq!(site, PhasesOpt(None, None, Some(Current::with_fix1(100))));
+        assert_eq!(
+            mapping.invert(site),
+            PhasesOpt(Some(Current::with_fix1(100)), None, None)
+        );
+        assert_eq!(mapping.inverse(), None);
+    }
+
+    #[test]
+    fn validation() {
+        assert_eq!(
+            PhaseMapping::permutation([Phase::L1, Phase::L3, Phase::L3]),
+            Err(MappingError::Repeated(Phase::L3))
+        );
+        assert_eq!(
+            Permutation::new([Phase::L2, Phase::L1, Phase::L2]),
+            Err(MappingError::Repeated(Phase::L2))
+        );
+        assert_eq!(
+            Permutation::new([Phase::L2, Phase::L3, Phase::L1]).map(|p| p.phases()),
+            Ok([Phase::L2, Phase::L3, Phase::L1])
+        );
+        assert_eq!(Permutation::IDENTITY.inverse(), Permutation::IDENTITY);
+    }
+
+    #[test]
+    fn serialization() {
+        let text = serde_json::to_string(&ROTATED).unwrap();
+        assert_eq!(text, r#"{"Permutation":["L2","L3","L1"]}"#);
+        assert_eq!(
+            serde_json::from_str::<PhaseMapping>(&text).unwrap(),
+            ROTATED
+        );
+        let single = PhaseMapping::Single(Phase::L2);
+        let text = serde_json::to_string(&single).unwrap();
+        assert_eq!(text, r#"{"Single":"L2"}"#);
+        assert_eq!(serde_json::from_str::<PhaseMapping>(&text).unwrap(), single);
+        assert!(
+            serde_json::from_str::<PhaseMapping>(r#"{"Permutation":["L2","L2","L1"]}"#).is_err()
+        );
+    }
+}